@@ -0,0 +1,286 @@
+//! 系统 DNS 解析缓存
+//!
+//! 修改 Hosts 或 DNS 服务器后，系统解析缓存中仍保留旧的结果，看起来像是修改没有生效。
+//! 这里提供刷新缓存（写入 Hosts / DNS 后自动调用）与查看某个名称缓存记录的功能。
+
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+#[cfg(target_os = "windows")]
+use winapi::um::winbase::CREATE_NO_WINDOW;
+
+/// 缓存中的一条记录
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DnsCacheEntry {
+    pub name: String,
+    /// 记录类型，如 A、AAAA、CNAME
+    pub record_type: String,
+    pub data: String,
+    /// 剩余生存时间（秒），部分平台无法获取
+    pub ttl: Option<u32>,
+}
+
+/// 刷新系统 DNS 解析缓存
+pub fn flush_resolver_cache() -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        // 优先直接调用 dnsapi.dll，失败时再退回 ipconfig /flushdns
+        if unsafe { flush_with_dnsapi() } {
+            return Ok(());
+        }
+        run_quiet(Command::new("ipconfig").arg("/flushdns"), "ipconfig /flushdns")
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        run_quiet(Command::new("resolvectl").arg("flush-caches"), "resolvectl flush-caches")
+            // 旧版 systemd 只有 systemd-resolve
+            .or_else(|e| {
+                run_quiet(
+                    Command::new("systemd-resolve").arg("--flush-caches"),
+                    "systemd-resolve --flush-caches",
+                )
+                .map_err(|_| e)
+            })
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    {
+        Err("当前系统不支持刷新 DNS 缓存".to_string())
+    }
+}
+
+/// 写入 Hosts / DNS 之后调用：刷新失败只记录日志，不影响写入本身的结果
+pub fn flush_after_change() {
+    if let Err(e) = flush_resolver_cache() {
+        eprintln!("刷新DNS缓存失败: {}", e);
+    }
+}
+
+#[cfg(target_os = "windows")]
+unsafe fn flush_with_dnsapi() -> bool {
+    use std::ffi::OsStr;
+    use std::os::windows::ffi::OsStrExt;
+    use winapi::um::libloaderapi::{FreeLibrary, GetProcAddress, LoadLibraryW};
+
+    // DnsFlushResolverCache 未在公开头文件中声明，只能动态获取
+    type DnsFlushResolverCache = unsafe extern "system" fn() -> i32;
+
+    let dll: Vec<u16> = OsStr::new("dnsapi.dll").encode_wide().chain(Some(0)).collect();
+    let module = LoadLibraryW(dll.as_ptr());
+    if module.is_null() {
+        return false;
+    }
+
+    let proc = GetProcAddress(module, b"DnsFlushResolverCache\0".as_ptr() as *const i8);
+    let flushed = if proc.is_null() {
+        false
+    } else {
+        let flush: DnsFlushResolverCache = std::mem::transmute(proc);
+        flush() != 0
+    };
+    FreeLibrary(module);
+    flushed
+}
+
+fn run_quiet(cmd: &mut Command, name: &str) -> Result<(), String> {
+    // Windows 下避免弹出控制台窗口
+    #[cfg(target_os = "windows")]
+    {
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let output = cmd.output().map_err(|e| format!("执行 {} 失败: {}", name, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} 失败: {}",
+            name,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// 查询系统缓存中某个名称的记录
+fn query_cache(name: &str) -> Result<Vec<DnsCacheEntry>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let output = crate::network::powershell_cmd()
+            .args([
+                "-Command",
+                &format!(
+                    "Get-DnsClientCache -Entry '{}' -ErrorAction SilentlyContinue | Select-Object Entry, Type, Data, TimeToLive | ConvertTo-Json -Compress",
+                    name.replace('\'', "''")
+                ),
+            ])
+            .output()
+            .map_err(|e| format!("执行 Get-DnsClientCache 失败: {}", e))?;
+        parse_windows_cache(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let output = Command::new("resolvectl")
+            .arg("show-cache")
+            .output()
+            .map_err(|e| format!("执行 resolvectl show-cache 失败: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "resolvectl show-cache 失败: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(parse_resolvectl_cache(&String::from_utf8_lossy(&output.stdout), name))
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    {
+        let _ = name;
+        Err("当前系统不支持查看 DNS 缓存".to_string())
+    }
+}
+
+/// 解析 `Get-DnsClientCache | ConvertTo-Json` 的输出（单条记录时不是数组）
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn parse_windows_cache(json: &str) -> Result<Vec<DnsCacheEntry>, String> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Record {
+        entry: String,
+        #[serde(rename = "Type")]
+        record_type: u16,
+        data: Option<String>,
+        time_to_live: Option<u32>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Records {
+        Many(Vec<Record>),
+        One(Record),
+    }
+
+    let json = json.trim();
+    if json.is_empty() {
+        return Ok(Vec::new());
+    }
+    let records = match serde_json::from_str::<Records>(json)
+        .map_err(|e| format!("解析 DNS 缓存失败: {}", e))?
+    {
+        Records::Many(records) => records,
+        Records::One(record) => vec![record],
+    };
+
+    Ok(records
+        .into_iter()
+        .map(|r| DnsCacheEntry {
+            name: r.entry,
+            record_type: record_type_name(r.record_type),
+            data: r.data.unwrap_or_default(),
+            ttl: r.time_to_live,
+        })
+        .collect())
+}
+
+/// 解析 `resolvectl show-cache` 的输出，只保留与 `name` 匹配的记录。
+/// 记录行形如 `example.com IN A 93.184.216.34`
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_resolvectl_cache(output: &str, name: &str) -> Vec<DnsCacheEntry> {
+    let name = name.trim().trim_end_matches('.').to_lowercase();
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut tokens = line.split_whitespace();
+            let record_name = tokens.next()?.trim_end_matches('.');
+            if tokens.next()? != "IN" || record_name.to_lowercase() != name {
+                return None;
+            }
+            let record_type = tokens.next()?.to_string();
+            let data = tokens.collect::<Vec<_>>().join(" ");
+            Some(DnsCacheEntry {
+                name: record_name.to_string(),
+                record_type,
+                data,
+                ttl: None,
+            })
+        })
+        .collect()
+}
+
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn record_type_name(value: u16) -> String {
+    match value {
+        1 => "A",
+        2 => "NS",
+        5 => "CNAME",
+        6 => "SOA",
+        12 => "PTR",
+        15 => "MX",
+        16 => "TXT",
+        28 => "AAAA",
+        33 => "SRV",
+        65 => "HTTPS",
+        other => return other.to_string(),
+    }
+    .to_string()
+}
+
+/// 手动刷新 DNS 缓存
+#[tauri::command]
+pub async fn flush_dns_cache() -> Result<(), String> {
+    tokio::task::spawn_blocking(flush_resolver_cache)
+        .await
+        .map_err(|e| format!("spawn_blocking 失败: {}", e))?
+}
+
+/// 查看系统缓存中某个名称的记录
+#[tauri::command]
+pub async fn get_dns_cache_entries(name: String) -> Result<Vec<DnsCacheEntry>, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("名称不能为空".to_string());
+    }
+    tokio::task::spawn_blocking(move || query_cache(&name))
+        .await
+        .map_err(|e| format!("spawn_blocking 失败: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_windows_cache() {
+        let one = r#"{"Entry":"example.com","Type":1,"Data":"93.184.216.34","TimeToLive":120}"#;
+        assert_eq!(
+            parse_windows_cache(one).unwrap(),
+            vec![DnsCacheEntry {
+                name: "example.com".to_string(),
+                record_type: "A".to_string(),
+                data: "93.184.216.34".to_string(),
+                ttl: Some(120),
+            }]
+        );
+
+        let many = r#"[{"Entry":"www.example.com","Type":5,"Data":"example.com","TimeToLive":30},{"Entry":"www.example.com","Type":99,"Data":null,"TimeToLive":null}]"#;
+        let entries = parse_windows_cache(many).unwrap();
+        assert_eq!(entries[0].record_type, "CNAME");
+        assert_eq!((entries[1].record_type.as_str(), entries[1].ttl), ("99", None));
+        assert!(parse_windows_cache("  ").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_resolvectl_cache() {
+        let output = "Scope protocol=dns interface=eth0\n\
+                      Example.com IN A 93.184.216.34\n\
+                      example.com IN AAAA 2606:2800:220:1::\n\
+                      other.com IN A 1.2.3.4\n\
+                      example.com IN TXT \"v=spf1 -all\"\n";
+        let entries = parse_resolvectl_cache(output, "example.com.");
+        let types: Vec<&str> = entries.iter().map(|e| e.record_type.as_str()).collect();
+        assert_eq!(types, vec!["A", "AAAA", "TXT"]);
+        assert_eq!(entries[2].data, "\"v=spf1 -all\"");
+    }
+}
@@ -1,9 +1,50 @@
 use std::fs;
 use std::path::PathBuf;
+use std::sync::OnceLock;
 use reqwest;
+use tauri::Emitter;
+
+mod allowlist;
+mod backup;
+mod convert;
+mod groups;
+mod lint;
+mod parser;
+mod path;
+mod subscriptions;
+mod validate;
+
+use crate::storage;
+pub use allowlist::SuppressedEntry;
+pub use backup::HostsBackup;
+pub use convert::SourceFormat;
+use groups::ManagedSection;
+pub use groups::HostsGroup;
+pub use lint::{LintIssue, NormalizeReport};
+pub use parser::HostsEntry;
+pub use path::HostsPathInfo;
+use path::HostsPathSource;
+use parser::HostsDocument;
+pub use subscriptions::HostsSubscription;
+use subscriptions::FetchOutcome;
+pub use validate::ValidatedHosts;
+
+const GROUPS_FILE: &str = "hosts_groups.json";
+const BACKUP_DIR: &str = "hosts_backups";
+const HOSTS_PATH_FILE: &str = "hosts_path.json";
+const SUBSCRIPTIONS_FILE: &str = "hosts_subscriptions.json";
+const SUBSCRIPTION_CACHE_DIR: &str = "hosts_subscriptions";
+const PROTECTED_DOMAINS_FILE: &str = "hosts_protected_domains.json";
+const ALLOWLIST_FILE: &str = "hosts_allowlist.json";
+
+/// 订阅列表的读写锁：定时刷新与用户修改订阅不能交叉进行
+fn get_subscriptions_mutex() -> &'static tokio::sync::Mutex<()> {
+    static INSTANCE: OnceLock<tokio::sync::Mutex<()>> = OnceLock::new();
+    INSTANCE.get_or_init(|| tokio::sync::Mutex::new(()))
+}
 
 fn get_hosts_path() -> PathBuf {
-    PathBuf::from("C:\\Windows\\System32\\drivers\\etc\\hosts")
+    path::resolve().0
 }
 
 #[tauri::command]
@@ -14,26 +55,78 @@ pub async fn get_hosts() -> Result<String, String> {
         .map_err(|e| format!("读取Hosts文件失败: {}", e))
 }
 
+/// 保存 Hosts 内容：受管区块之外的内容按编辑结果写入，区块本身按已启用的分组重新生成
 #[tauri::command]
-pub async fn set_hosts(content: String) -> Result<(), String> {
+pub async fn set_hosts(app: tauri::AppHandle, content: String) -> Result<(), String> {
+    let sections = managed_sections(&app)?;
+    write_hosts_file(&app, &groups::apply_managed_block(&content, &sections))
+}
+
+/// 写入 Hosts 文件（写入前把原文件备份到应用数据目录）
+fn write_hosts_file(app: &tauri::AppHandle, content: &str) -> Result<(), String> {
     let hosts_path = get_hosts_path();
     
     // 备份原文件
-    let backup_path = hosts_path.with_extension("hosts.bak");
     if hosts_path.exists() {
-        fs::copy(&hosts_path, &backup_path)
-            .map_err(|e| format!("备份Hosts文件失败: {}", e))?;
+        let current = fs::read_to_string(&hosts_path)
+            .map_err(|e| format!("读取Hosts文件失败: {}", e))?;
+        backup::create_backup(&backup_dir(app)?, &current, crate::scenes::now_millis())?;
     }
     
     // 写入新内容
     fs::write(&hosts_path, content)
         .map_err(|e| format!("写入Hosts文件失败: {}. 请确保以管理员权限运行", e))?;
     
+    // 刷新解析缓存，否则修改后仍会命中旧的解析结果（编辑其他位置的文件时不需要）
+    if path::resolve().1 == HostsPathSource::System {
+        crate::dns::flush_after_change();
+    }
+    
+    Ok(())
+}
+
+/// 应用启动时恢复手动指定的 Hosts 路径
+pub fn init_hosts_path(app: &tauri::AppHandle) -> Result<(), String> {
+    let saved: Option<String> = storage::load_json(&storage::app_data_file(app, HOSTS_PATH_FILE)?)?;
+    path::set_override(saved.map(PathBuf::from));
     Ok(())
 }
 
+/// 获取当前使用的 Hosts 路径及其来源
+#[tauri::command]
+pub async fn get_hosts_path_info() -> Result<HostsPathInfo, String> {
+    let (hosts_path, source) = path::resolve();
+    Ok(HostsPathInfo {
+        exists: hosts_path.is_file(),
+        path: hosts_path.to_string_lossy().to_string(),
+        source,
+    })
+}
+
+/// 手动指定 Hosts 路径（如挂载镜像中的文件），传空值恢复使用系统路径
 #[tauri::command]
-pub async fn fetch_remote_hosts(url: String) -> Result<String, String> {
+pub async fn set_hosts_path(app: tauri::AppHandle, path: Option<String>) -> Result<HostsPathInfo, String> {
+    let path = path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty());
+    if let Some(p) = &path {
+        if !PathBuf::from(p).is_file() {
+            return Err(format!("文件不存在: {}", p));
+        }
+    }
+    storage::save_json(&storage::app_data_file(&app, HOSTS_PATH_FILE)?, &path)?;
+    path::set_override(path.map(PathBuf::from));
+    get_hosts_path_info().await
+}
+
+/// 下载远程 Hosts 内容（可指定 Adblock 等格式转换为 Hosts 条目），
+/// 返回校验清理后的内容与被拒绝行的报告
+#[tauri::command]
+pub async fn fetch_remote_hosts(
+    app: tauri::AppHandle,
+    url: String,
+    format: Option<SourceFormat>,
+    sink_ip: Option<String>,
+) -> Result<ValidatedHosts, String> {
+    let sink_ip = convert::sink_ip(sink_ip.as_deref())?;
     let client = reqwest::Client::builder()
         .timeout(std::time::Duration::from_secs(30))
         .build()
@@ -49,14 +142,538 @@ pub async fn fetch_remote_hosts(url: String) -> Result<String, String> {
         return Err(format!("HTTP错误: {} {}", response.status().as_u16(), response.status().as_str()));
     }
     
-    let content = response
-        .text()
-        .await
-        .map_err(|e| format!("读取响应内容失败: {}", e))?;
-    
-    if content.trim().is_empty() {
-        return Err("远程内容为空".to_string());
+    let content = validate::read_response(response).await?;
+    let content = convert::to_hosts(&content, format.unwrap_or_default(), &sink_ip);
+    sanitize_remote(&app, &content)
+}
+
+/// 读取并解析当前 Hosts 文件
+fn read_hosts_document() -> Result<HostsDocument, String> {
+    let content = fs::read_to_string(get_hosts_path())
+        .map_err(|e| format!("读取Hosts文件失败: {}", e))?;
+    Ok(HostsDocument::parse(&content))
+}
+
+/// 修改 Hosts 语法树并写回，返回修改后的条目列表
+fn edit_hosts_document(
+    app: &tauri::AppHandle,
+    edit: impl FnOnce(&mut HostsDocument) -> Result<(), String>,
+) -> Result<Vec<HostsEntry>, String> {
+    let mut doc = read_hosts_document()?;
+    edit(&mut doc)?;
+    write_hosts_file(app, &doc.render())?;
+    Ok(doc.entries())
+}
+
+/// 获取 Hosts 条目列表（包括被注释掉的“已禁用”条目）
+#[tauri::command]
+pub async fn get_hosts_entries() -> Result<Vec<HostsEntry>, String> {
+    Ok(read_hosts_document()?.entries())
+}
+
+/// 在 Hosts 文件末尾添加条目
+#[tauri::command]
+pub async fn add_hosts_entry(
+    app: tauri::AppHandle,
+    ip: String,
+    hostnames: Vec<String>,
+    comment: Option<String>,
+    enabled: Option<bool>,
+) -> Result<Vec<HostsEntry>, String> {
+    edit_hosts_document(&app, |doc| {
+        doc.add_entry(&ip, &hostnames, comment.as_deref(), enabled.unwrap_or(true))
+            .map(|_| ())
+    })
+}
+
+/// 删除指定行的条目
+#[tauri::command]
+pub async fn remove_hosts_entry(app: tauri::AppHandle, line: usize) -> Result<Vec<HostsEntry>, String> {
+    edit_hosts_document(&app, |doc| doc.remove_entry(line))
+}
+
+/// 启用/禁用指定行的条目（禁用即在行首加 `#`）
+#[tauri::command]
+pub async fn toggle_hosts_entry(
+    app: tauri::AppHandle,
+    line: usize,
+    enabled: bool,
+) -> Result<Vec<HostsEntry>, String> {
+    edit_hosts_document(&app, |doc| doc.set_enabled(line, enabled))
+}
+
+/// 检查 Hosts 内容：重复映射、冲突、无效 IP、过长的行以及被前面条目遮蔽的行。
+/// 未提供内容时检查当前 Hosts 文件（编辑器可传入尚未保存的内容）
+#[tauri::command]
+pub async fn lint_hosts(content: Option<String>) -> Result<Vec<LintIssue>, String> {
+    let doc = match content {
+        Some(content) => HostsDocument::parse(&content),
+        None => read_hosts_document()?,
+    };
+    Ok(lint::lint(&doc))
+}
+
+/// 一键整理 Hosts 文件：去除重复映射，并在每组连续条目内排序
+#[tauri::command]
+pub async fn normalize_hosts(app: tauri::AppHandle) -> Result<NormalizeReport, String> {
+    let doc = read_hosts_document()?;
+    let (content, removed_mappings) = lint::normalize(&doc);
+    if content != doc.render() {
+        write_hosts_file(&app, &content)?;
     }
-    
-    Ok(content)
+    Ok(NormalizeReport {
+        removed_mappings,
+        remaining_issues: lint::lint(&HostsDocument::parse(&content)),
+    })
+}
+
+/// 修改指定行条目的 IP、主机名与行内注释
+#[tauri::command]
+pub async fn update_hosts_entry(
+    app: tauri::AppHandle,
+    line: usize,
+    ip: String,
+    hostnames: Vec<String>,
+    comment: Option<String>,
+) -> Result<Vec<HostsEntry>, String> {
+    edit_hosts_document(&app, |doc| doc.update_entry(line, &ip, &hostnames, comment.as_deref()))
+}
+
+fn load_groups(app: &tauri::AppHandle) -> Result<Vec<HostsGroup>, String> {
+    storage::load_json(&storage::app_data_file(app, GROUPS_FILE)?)
+}
+
+fn save_groups(app: &tauri::AppHandle, groups: &[HostsGroup]) -> Result<(), String> {
+    storage::save_json(&storage::app_data_file(app, GROUPS_FILE)?, &groups)
+}
+
+/// 受管区块中的各段内容（已启用的分组，以及已启用且有缓存内容的订阅）
+fn managed_sections(app: &tauri::AppHandle) -> Result<Vec<ManagedSection>, String> {
+    let mut sections: Vec<ManagedSection> = load_groups(app)?
+        .iter()
+        .filter(|g| g.enabled)
+        .map(ManagedSection::group)
+        .collect();
+
+    for sub in load_subscriptions(app)?.iter().filter(|s| s.enabled) {
+        if let Ok(content) = fs::read_to_string(subscription_cache_path(app, sub)?) {
+            sections.push(sub.section(&merge_subscription(app, sub, &content)?.0.content));
+        }
+    }
+    Ok(sections)
+}
+
+/// 按当前分组重新生成 Hosts 文件中的受管区块
+fn regenerate_managed_block(app: &tauri::AppHandle) -> Result<(), String> {
+    let hosts_path = get_hosts_path();
+    let content = fs::read_to_string(&hosts_path)
+        .map_err(|e| format!("读取Hosts文件失败: {}", e))?;
+    let updated = groups::apply_managed_block(&content, &managed_sections(app)?);
+    if updated != content {
+        write_hosts_file(app, &updated)?;
+    }
+    Ok(())
+}
+
+/// 修改分组列表，保存后重新生成 Hosts 文件
+fn edit_groups(
+    app: &tauri::AppHandle,
+    edit: impl FnOnce(&mut Vec<HostsGroup>) -> Result<(), String>,
+) -> Result<Vec<HostsGroup>, String> {
+    let mut groups = load_groups(app)?;
+    edit(&mut groups)?;
+    save_groups(app, &groups)?;
+    regenerate_managed_block(app)?;
+    Ok(groups)
+}
+
+/// 获取所有 Hosts 分组
+#[tauri::command]
+pub async fn list_hosts_groups(app: tauri::AppHandle) -> Result<Vec<HostsGroup>, String> {
+    load_groups(&app)
+}
+
+/// 新建或修改 Hosts 分组（按名称匹配）
+#[tauri::command]
+pub async fn save_hosts_group(
+    app: tauri::AppHandle,
+    name: String,
+    content: String,
+    enabled: Option<bool>,
+) -> Result<Vec<HostsGroup>, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("分组名称不能为空".to_string());
+    }
+    edit_groups(&app, |groups| {
+        match groups.iter_mut().find(|g| g.name == name) {
+            Some(group) => {
+                group.content = content;
+                if let Some(enabled) = enabled {
+                    group.enabled = enabled;
+                }
+            }
+            None => groups.push(HostsGroup {
+                name,
+                enabled: enabled.unwrap_or(true),
+                content,
+            }),
+        }
+        Ok(())
+    })
+}
+
+/// 删除 Hosts 分组
+#[tauri::command]
+pub async fn delete_hosts_group(app: tauri::AppHandle, name: String) -> Result<Vec<HostsGroup>, String> {
+    edit_groups(&app, |groups| {
+        let before = groups.len();
+        groups.retain(|g| g.name != name);
+        if groups.len() == before {
+            return Err(format!("分组 {} 不存在", name));
+        }
+        Ok(())
+    })
+}
+
+/// 启用/禁用 Hosts 分组
+#[tauri::command]
+pub async fn set_hosts_group_enabled(
+    app: tauri::AppHandle,
+    name: String,
+    enabled: bool,
+) -> Result<Vec<HostsGroup>, String> {
+    edit_groups(&app, |groups| {
+        let group = groups
+            .iter_mut()
+            .find(|g| g.name == name)
+            .ok_or_else(|| format!("分组 {} 不存在", name))?;
+        group.enabled = enabled;
+        Ok(())
+    })
+}
+
+fn load_protected_domains(app: &tauri::AppHandle) -> Result<Vec<String>, String> {
+    storage::load_json(&storage::app_data_file(app, PROTECTED_DOMAINS_FILE)?)
+}
+
+/// 按受保护域名等规则清理远程内容
+fn sanitize_remote(app: &tauri::AppHandle, content: &str) -> Result<ValidatedHosts, String> {
+    Ok(validate::sanitize(content, &load_protected_domains(app)?))
+}
+
+fn load_allowlist(app: &tauri::AppHandle) -> Result<Vec<String>, String> {
+    storage::load_json(&storage::app_data_file(app, ALLOWLIST_FILE)?)
+}
+
+/// 订阅内容合并前的处理：格式转换 → 按受保护域名清理 → 移除白名单中的名称
+fn merge_subscription(
+    app: &tauri::AppHandle,
+    sub: &HostsSubscription,
+    raw: &str,
+) -> Result<(ValidatedHosts, Vec<SuppressedEntry>), String> {
+    let mut validated = sanitize_remote(app, &sub.hosts_content(raw))?;
+    let (content, suppressed) = allowlist::apply(&validated.content, &load_allowlist(app)?, &sub.name);
+    validated.content = content;
+    Ok((validated, suppressed))
+}
+
+/// 按当前规则重新统计已缓存订阅的条目数与校验结果
+fn recount_subscriptions(app: &tauri::AppHandle, subs: &mut [HostsSubscription]) -> Result<(), String> {
+    for sub in subs.iter_mut() {
+        if let Ok(content) = fs::read_to_string(subscription_cache_path(app, sub)?) {
+            let (validated, _) = merge_subscription(app, sub, &content)?;
+            sub.entry_count = subscriptions::count_entries(&validated.content);
+            sub.report = validated.report;
+        }
+    }
+    Ok(())
+}
+
+fn load_subscriptions(app: &tauri::AppHandle) -> Result<Vec<HostsSubscription>, String> {
+    storage::load_json(&storage::app_data_file(app, SUBSCRIPTIONS_FILE)?)
+}
+
+fn save_subscriptions(app: &tauri::AppHandle, subs: &[HostsSubscription]) -> Result<(), String> {
+    storage::save_json(&storage::app_data_file(app, SUBSCRIPTIONS_FILE)?, &subs)
+}
+
+fn subscription_cache_path(app: &tauri::AppHandle, sub: &HostsSubscription) -> Result<PathBuf, String> {
+    let dir = storage::app_data_file(app, SUBSCRIPTION_CACHE_DIR)?;
+    fs::create_dir_all(&dir).map_err(|e| format!("创建订阅缓存目录失败: {}", e))?;
+    Ok(dir.join(sub.cache_file_name()))
+}
+
+/// 修改订阅列表，保存后重新生成 Hosts 文件
+async fn edit_subscriptions(
+    app: &tauri::AppHandle,
+    edit: impl FnOnce(&mut Vec<HostsSubscription>) -> Result<(), String>,
+) -> Result<Vec<HostsSubscription>, String> {
+    let _guard = get_subscriptions_mutex().lock().await;
+    let mut subs = load_subscriptions(app)?;
+    edit(&mut subs)?;
+    save_subscriptions(app, &subs)?;
+    regenerate_managed_block(app)?;
+    Ok(subs)
+}
+
+/// 刷新被选中的订阅（`select` 的第二个参数为当前时间）
+async fn refresh_subscriptions(
+    app: &tauri::AppHandle,
+    select: impl Fn(&HostsSubscription, u64) -> bool,
+) -> Result<Vec<HostsSubscription>, String> {
+    let _guard = get_subscriptions_mutex().lock().await;
+    let mut subs = load_subscriptions(app)?;
+    let now = crate::scenes::now_millis();
+
+    let selected: Vec<usize> = subs
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| select(s, now))
+        .map(|(i, _)| i)
+        .collect();
+    if selected.is_empty() {
+        return Ok(subs);
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(30))
+        .build()
+        .map_err(|e| format!("创建HTTP客户端失败: {}", e))?;
+
+    let mut changed = false;
+    for index in selected {
+        let sub = &mut subs[index];
+        let cache_path = subscription_cache_path(app, sub)?;
+        // 缓存文件丢失时不能依赖 304，重新完整下载
+        if !cache_path.exists() {
+            sub.etag = None;
+            sub.last_modified = None;
+        }
+
+        let result = match subscriptions::fetch(&client, sub).await {
+            Ok(FetchOutcome::NotModified) => Ok(None),
+            Ok(FetchOutcome::Updated { content, etag, last_modified }) => {
+                // 缓存原始内容，写入 Hosts 时再按当前规则清理
+                let (validated, _) = merge_subscription(app, sub, &content)?;
+                fs::write(&cache_path, &content)
+                    .map(|_| {
+                        sub.etag = etag;
+                        sub.last_modified = last_modified;
+                        sub.report = validated.report;
+                        changed = true;
+                        Some(subscriptions::count_entries(&validated.content))
+                    })
+                    .map_err(|e| format!("写入订阅缓存失败: {}", e))
+            }
+            Err(e) => Err(e),
+        };
+        sub.record(result, now);
+    }
+
+    save_subscriptions(app, &subs)?;
+    if changed {
+        regenerate_managed_block(app)?;
+    }
+    let _ = app.emit("hosts-subscriptions-updated", &subs);
+    Ok(subs)
+}
+
+/// 启动订阅定时刷新（每分钟检查一次是否有到期的订阅）
+pub fn start_hosts_subscription_scheduler(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = refresh_subscriptions(&app, |s, now| s.is_due(now)).await {
+                eprintln!("刷新Hosts订阅失败: {}", e);
+            }
+            tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
+        }
+    });
+}
+
+/// 获取所有 Hosts 订阅及其状态
+#[tauri::command]
+pub async fn list_hosts_subscriptions(app: tauri::AppHandle) -> Result<Vec<HostsSubscription>, String> {
+    load_subscriptions(&app)
+}
+
+/// 新建或修改 Hosts 订阅（按名称匹配），URL 变化时清除缓存信息。
+/// 修改时未指定的间隔、启用状态等保持不变
+#[tauri::command]
+pub async fn save_hosts_subscription(
+    app: tauri::AppHandle,
+    name: String,
+    url: String,
+    interval_minutes: Option<u64>,
+    enabled: Option<bool>,
+    format: Option<SourceFormat>,
+    sink_ip: Option<String>,
+) -> Result<Vec<HostsSubscription>, String> {
+    let name = name.trim().to_string();
+    let url = url.trim().to_string();
+    if name.is_empty() {
+        return Err("订阅名称不能为空".to_string());
+    }
+    reqwest::Url::parse(&url).map_err(|_| format!("无效的订阅地址: {}", url))?;
+    let interval_minutes = interval_minutes.map(|minutes| minutes.max(subscriptions::MIN_INTERVAL_MINUTES));
+    if let Some(sink_ip) = &sink_ip {
+        convert::sink_ip(Some(sink_ip))?;
+    }
+
+    edit_subscriptions(&app, |subs| {
+        match subs.iter_mut().find(|s| s.name == name) {
+            Some(sub) => {
+                if sub.url != url {
+                    let _ = fs::remove_file(subscription_cache_path(&app, sub)?);
+                    *sub = HostsSubscription {
+                        name: sub.name.clone(),
+                        enabled: sub.enabled,
+                        ..Default::default()
+                    };
+                    sub.url = url;
+                }
+                if let Some(interval_minutes) = interval_minutes {
+                    sub.interval_minutes = interval_minutes;
+                }
+                if let Some(enabled) = enabled {
+                    sub.enabled = enabled;
+                }
+                if let Some(format) = format {
+                    sub.format = format;
+                }
+                if sink_ip.is_some() {
+                    sub.sink_ip = sink_ip;
+                }
+            }
+            None => subs.push(HostsSubscription {
+                name,
+                url,
+                enabled: enabled.unwrap_or(true),
+                interval_minutes: interval_minutes.unwrap_or(subscriptions::DEFAULT_INTERVAL_MINUTES),
+                format: format.unwrap_or_default(),
+                sink_ip,
+                ..Default::default()
+            }),
+        }
+        Ok(())
+    })
+    .await
+}
+
+/// 删除 Hosts 订阅，同时移除其缓存与写入 Hosts 文件的内容
+#[tauri::command]
+pub async fn delete_hosts_subscription(
+    app: tauri::AppHandle,
+    name: String,
+) -> Result<Vec<HostsSubscription>, String> {
+    let mut removed = None;
+    let subs = edit_subscriptions(&app, |subs| {
+        let index = subs
+            .iter()
+            .position(|s| s.name == name)
+            .ok_or_else(|| format!("订阅 {} 不存在", name))?;
+        removed = Some(subs.remove(index));
+        Ok(())
+    })
+    .await?;
+    if let Some(sub) = removed {
+        let _ = fs::remove_file(subscription_cache_path(&app, &sub)?);
+    }
+    Ok(subs)
+}
+
+/// 立即刷新 Hosts 订阅：指定名称时只刷新该订阅，否则刷新所有已启用的订阅
+#[tauri::command]
+pub async fn refresh_hosts_subscriptions(
+    app: tauri::AppHandle,
+    name: Option<String>,
+) -> Result<Vec<HostsSubscription>, String> {
+    match name {
+        Some(name) => {
+            if !load_subscriptions(&app)?.iter().any(|s| s.name == name) {
+                return Err(format!("订阅 {} 不存在", name));
+            }
+            refresh_subscriptions(&app, |s, _| s.name == name).await
+        }
+        None => refresh_subscriptions(&app, |s, _| s.enabled).await,
+    }
+}
+
+/// 获取受保护的域名（如公司内部域名），订阅内容不能覆盖这些域名及其子域名
+#[tauri::command]
+pub async fn get_hosts_protected_domains(app: tauri::AppHandle) -> Result<Vec<String>, String> {
+    load_protected_domains(&app)
+}
+
+/// 设置受保护的域名，并按新规则重新校验已缓存的订阅内容
+#[tauri::command]
+pub async fn set_hosts_protected_domains(
+    app: tauri::AppHandle,
+    domains: Vec<String>,
+) -> Result<Vec<String>, String> {
+    let domains: Vec<String> = domains
+        .iter()
+        .map(|d| d.trim().to_lowercase())
+        .filter(|d| !d.is_empty())
+        .collect();
+    storage::save_json(&storage::app_data_file(&app, PROTECTED_DOMAINS_FILE)?, &domains)?;
+
+    edit_subscriptions(&app, |subs| recount_subscriptions(&app, subs)).await?;
+    Ok(domains)
+}
+
+/// 获取 Hosts 白名单
+#[tauri::command]
+pub async fn get_hosts_allowlist(app: tauri::AppHandle) -> Result<Vec<String>, String> {
+    load_allowlist(&app)
+}
+
+/// 设置 Hosts 白名单（精确名称或 `*.example.com`），并重新生成订阅内容
+#[tauri::command]
+pub async fn set_hosts_allowlist(app: tauri::AppHandle, rules: Vec<String>) -> Result<Vec<String>, String> {
+    let rules = allowlist::normalize_rules(&rules)?;
+    storage::save_json(&storage::app_data_file(&app, ALLOWLIST_FILE)?, &rules)?;
+
+    edit_subscriptions(&app, |subs| recount_subscriptions(&app, subs)).await?;
+    Ok(rules)
+}
+
+/// 列出已启用订阅中被白名单移除的映射
+#[tauri::command]
+pub async fn get_hosts_allowlist_suppressed(app: tauri::AppHandle) -> Result<Vec<SuppressedEntry>, String> {
+    let mut suppressed = Vec::new();
+    for sub in load_subscriptions(&app)?.iter().filter(|s| s.enabled) {
+        if let Ok(content) = fs::read_to_string(subscription_cache_path(&app, sub)?) {
+            suppressed.extend(merge_subscription(&app, sub, &content)?.1);
+        }
+    }
+    Ok(suppressed)
+}
+
+fn backup_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    storage::app_data_file(app, BACKUP_DIR)
+}
+
+/// 获取 Hosts 备份列表（最新的在前）
+#[tauri::command]
+pub async fn list_hosts_backups(app: tauri::AppHandle) -> Result<Vec<HostsBackup>, String> {
+    backup::list_backups(&backup_dir(&app)?)
+}
+
+/// 比较备份与当前 Hosts 文件，返回 unified diff（无差异时为空字符串）
+#[tauri::command]
+pub async fn diff_hosts_backup(app: tauri::AppHandle, id: String) -> Result<String, String> {
+    let path = backup::backup_path(&backup_dir(&app)?, &id)?;
+    let old = fs::read_to_string(path).map_err(|e| format!("读取备份失败: {}", e))?;
+    let current = fs::read_to_string(get_hosts_path())
+        .map_err(|e| format!("读取Hosts文件失败: {}", e))?;
+    Ok(backup::unified_diff(&old, &current, &id, "hosts"))
+}
+
+/// 用备份覆盖当前 Hosts 文件（覆盖前当前内容同样会被备份，可再次恢复）
+#[tauri::command]
+pub async fn restore_hosts_backup(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    let path = backup::backup_path(&backup_dir(&app)?, &id)?;
+    let content = fs::read_to_string(path).map_err(|e| format!("读取备份失败: {}", e))?;
+    write_hosts_file(&app, &content)
 }
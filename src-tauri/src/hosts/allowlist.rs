@@ -0,0 +1,150 @@
+//! Hosts 白名单
+//!
+//! 订阅内容合并进 Hosts 时，白名单中的名称会从条目中移除，避免拦截列表误伤需要的域名。
+//! 规则可以是精确的主机名（`example.com`），也可以是通配后缀（`*.example.com`，
+//! 匹配 example.com 本身及其所有子域名）。
+
+use super::parser::{format_entry, EntryData, HostsDocument, LineKind};
+use serde::{Deserialize, Serialize};
+
+/// 被白名单移除的映射
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SuppressedEntry {
+    /// 来源（订阅名称）
+    pub source: String,
+    pub hostname: String,
+    pub ip: String,
+    /// 命中的白名单规则
+    pub rule: String,
+}
+
+/// 校验并规范化规则
+pub fn normalize_rules(rules: &[String]) -> Result<Vec<String>, String> {
+    let mut result: Vec<String> = Vec::new();
+    for rule in rules {
+        let rule = rule.trim().trim_end_matches('.').to_lowercase();
+        if rule.is_empty() {
+            continue;
+        }
+        let domain = rule.strip_prefix("*.").unwrap_or(&rule);
+        let valid = !domain.is_empty()
+            && domain.split('.').all(|label| {
+                !label.is_empty()
+                    && label
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            });
+        if !valid {
+            return Err(format!("无效的白名单规则: {}", rule));
+        }
+        if !result.contains(&rule) {
+            result.push(rule);
+        }
+    }
+    Ok(result)
+}
+
+/// 返回命中的规则
+pub fn matching_rule<'a>(hostname: &str, rules: &'a [String]) -> Option<&'a String> {
+    let hostname = hostname.trim_end_matches('.').to_lowercase();
+    rules.iter().find(|rule| match rule.strip_prefix("*.") {
+        Some(suffix) => {
+            hostname == suffix
+                || hostname
+                    .strip_suffix(suffix)
+                    .is_some_and(|prefix| prefix.ends_with('.'))
+        }
+        None => hostname == **rule,
+    })
+}
+
+/// 从 Hosts 内容中移除白名单中的名称，返回处理后的内容与被移除的映射
+pub fn apply(content: &str, rules: &[String], source: &str) -> (String, Vec<SuppressedEntry>) {
+    if rules.is_empty() {
+        return (content.to_string(), Vec::new());
+    }
+
+    let doc = HostsDocument::parse(content);
+    let mut suppressed = Vec::new();
+    let mut result = String::new();
+
+    for line in &doc.lines {
+        let data = match &line.kind {
+            LineKind::Entry(data) if data.enabled => data,
+            _ => {
+                result.push_str(line.text());
+                result.push_str(doc.newline());
+                continue;
+            }
+        };
+
+        let mut hostnames = Vec::new();
+        for hostname in &data.hostnames {
+            match matching_rule(hostname, rules) {
+                Some(rule) => suppressed.push(SuppressedEntry {
+                    source: source.to_string(),
+                    hostname: hostname.clone(),
+                    ip: data.ip.clone(),
+                    rule: rule.clone(),
+                }),
+                None => hostnames.push(hostname.clone()),
+            }
+        }
+
+        if hostnames.len() == data.hostnames.len() {
+            result.push_str(line.text());
+        } else if hostnames.is_empty() {
+            continue;
+        } else {
+            result.push_str(&format_entry(&EntryData {
+                hostnames,
+                ..data.clone()
+            }));
+        }
+        result.push_str(doc.newline());
+    }
+
+    (result, suppressed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allowlist_suppresses_exact_and_wildcard_names() {
+        let rules = normalize_rules(&[
+            " Login.Example.com ".to_string(),
+            "*.cdn.example.net.".to_string(),
+            String::new(),
+        ])
+        .unwrap();
+        assert_eq!(rules, vec!["login.example.com", "*.cdn.example.net"]);
+        assert!(normalize_rules(&["*.".to_string()]).is_err());
+        assert!(normalize_rules(&["a b".to_string()]).is_err());
+
+        let content = "# ads\n\
+                       0.0.0.0 ads.example.com login.example.com\n\
+                       0.0.0.0 cdn.example.net\n\
+                       0.0.0.0 img.cdn.example.net\n\
+                       0.0.0.0 notcdn.example.net\n\
+                       # 0.0.0.0 login.example.com\n";
+        let (result, suppressed) = apply(content, &rules, "ads");
+        assert_eq!(
+            result,
+            "# ads\n0.0.0.0\tads.example.com\n0.0.0.0 notcdn.example.net\n# 0.0.0.0 login.example.com\n"
+        );
+        let names: Vec<(&str, &str)> = suppressed
+            .iter()
+            .map(|s| (s.hostname.as_str(), s.rule.as_str()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("login.example.com", "login.example.com"),
+                ("cdn.example.net", "*.cdn.example.net"),
+                ("img.cdn.example.net", "*.cdn.example.net"),
+            ]
+        );
+    }
+}
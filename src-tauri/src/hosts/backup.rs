@@ -0,0 +1,132 @@
+//! Hosts 备份
+//!
+//! 每次写入 Hosts 前把原文件保存到应用数据目录下，文件名带时间戳，
+//! 只保留最近的若干份。内容与最近一份备份相同时不重复备份。
+
+use serde::{Deserialize, Serialize};
+use similar::TextDiff;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 保留的备份数量
+pub const MAX_BACKUPS: usize = 30;
+
+const PREFIX: &str = "hosts-";
+const EXTENSION: &str = ".bak";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HostsBackup {
+    /// 备份标识（即文件名）
+    pub id: String,
+    pub created_at: u64,
+    pub size: u64,
+}
+
+/// 备份内容并清理超出保留数量的旧备份；返回新备份（内容未变化时为 None）
+pub fn create_backup(dir: &Path, content: &str, now: u64) -> Result<Option<HostsBackup>, String> {
+    fs::create_dir_all(dir).map_err(|e| format!("创建备份目录失败: {}", e))?;
+
+    let backups = list_backups(dir)?;
+    if let Some(latest) = backups.first() {
+        if fs::read_to_string(dir.join(&latest.id)).ok().as_deref() == Some(content) {
+            return Ok(None);
+        }
+    }
+
+    // 同一毫秒内多次写入时顺延，避免覆盖
+    let mut created_at = now;
+    while backups.iter().any(|b| b.created_at == created_at) {
+        created_at += 1;
+    }
+    let id = format!("{}{}{}", PREFIX, created_at, EXTENSION);
+    fs::write(dir.join(&id), content).map_err(|e| format!("备份Hosts文件失败: {}", e))?;
+
+    for old in backups.iter().skip(MAX_BACKUPS.saturating_sub(1)) {
+        let _ = fs::remove_file(dir.join(&old.id));
+    }
+
+    Ok(Some(HostsBackup {
+        id,
+        created_at,
+        size: content.len() as u64,
+    }))
+}
+
+/// 列出备份，最新的在前
+pub fn list_backups(dir: &Path) -> Result<Vec<HostsBackup>, String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("读取备份目录失败: {}", e)),
+    };
+
+    let mut backups: Vec<HostsBackup> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let id = entry.file_name().to_string_lossy().to_string();
+            let created_at = parse_id(&id)?;
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            Some(HostsBackup { id, created_at, size })
+        })
+        .collect();
+    backups.sort_by_key(|b| std::cmp::Reverse(b.created_at));
+    Ok(backups)
+}
+
+/// 备份文件路径（校验标识，防止访问备份目录之外的文件）
+pub fn backup_path(dir: &Path, id: &str) -> Result<PathBuf, String> {
+    parse_id(id).ok_or_else(|| format!("无效的备份: {}", id))?;
+    let path = dir.join(id);
+    if !path.exists() {
+        return Err(format!("备份 {} 不存在", id));
+    }
+    Ok(path)
+}
+
+/// 生成统一格式（unified diff）的差异
+pub fn unified_diff(old: &str, new: &str, old_label: &str, new_label: &str) -> String {
+    TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(3)
+        .header(old_label, new_label)
+        .to_string()
+}
+
+fn parse_id(id: &str) -> Option<u64> {
+    id.strip_prefix(PREFIX)?.strip_suffix(EXTENSION)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backups_rotate_and_skip_unchanged_content() {
+        let dir = std::env::temp_dir().join(format!("ip-switch-backup-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        assert!(create_backup(&dir, "v0", 1000).unwrap().is_some());
+        assert!(create_backup(&dir, "v0", 2000).unwrap().is_none());
+        for i in 1..=MAX_BACKUPS as u64 + 5 {
+            create_backup(&dir, &format!("v{}", i), 1000 + i).unwrap();
+        }
+
+        let backups = list_backups(&dir).unwrap();
+        assert_eq!(backups.len(), MAX_BACKUPS);
+        assert_eq!(backups[0].created_at, 1000 + MAX_BACKUPS as u64 + 5);
+        assert_eq!(
+            fs::read_to_string(backup_path(&dir, &backups[0].id).unwrap()).unwrap(),
+            format!("v{}", MAX_BACKUPS + 5)
+        );
+        assert!(backup_path(&dir, "../hosts").is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_unified_diff() {
+        let diff = unified_diff("a\nb\nc\n", "a\nB\nc\n", "backup", "current");
+        assert_eq!(diff, "--- backup\n+++ current\n@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n");
+        assert_eq!(unified_diff("a\n", "a\n", "x", "y"), "");
+    }
+}
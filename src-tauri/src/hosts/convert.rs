@@ -0,0 +1,264 @@
+//! 把其他格式的拦截列表转换为 Hosts 条目
+//!
+//! 支持 Adblock/ABP 的 `||domain^` 规则、dnsmasq 的 `address=/domain/0.0.0.0`、
+//! unbound 的 `local-zone` / `local-data`，以及每行一个域名的纯列表。
+//! 拦截规则转换后指向“黑洞”地址（默认 `0.0.0.0`）；dnsmasq/unbound 中指向其他地址的
+//! 规则是重定向，保留原地址。转换结果之后再按 Hosts 内容做校验。
+//! 例外规则、元素隐藏规则等无法用 Hosts 表达的规则会被忽略。
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::net::IpAddr;
+
+pub const DEFAULT_SINK_IP: &str = "0.0.0.0";
+
+/// 转换出的条目 `(目标地址, 域名)`，目标地址为 None 时指向黑洞地址
+type Rule = (Option<String>, String);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceFormat {
+    /// 标准 Hosts 格式，不做转换
+    #[default]
+    Hosts,
+    Adblock,
+    Dnsmasq,
+    Unbound,
+    /// 每行一个域名
+    Domains,
+    /// 根据内容自动判断
+    Auto,
+}
+
+/// 校验黑洞地址，未指定时使用默认值
+pub fn sink_ip(value: Option<&str>) -> Result<String, String> {
+    let value = value.map(str::trim).filter(|v| !v.is_empty()).unwrap_or(DEFAULT_SINK_IP);
+    value
+        .parse::<IpAddr>()
+        .map(|ip| ip.to_string())
+        .map_err(|_| format!("无效的黑洞地址: {}", value))
+}
+
+/// 转换为 Hosts 格式的内容
+pub fn to_hosts(content: &str, format: SourceFormat, sink_ip: &str) -> String {
+    let format = match format {
+        SourceFormat::Auto => detect(content),
+        other => other,
+    };
+    let parse: fn(&str) -> Vec<Rule> = match format {
+        SourceFormat::Hosts | SourceFormat::Auto => return content.to_string(),
+        SourceFormat::Adblock => parse_adblock_line,
+        SourceFormat::Dnsmasq => parse_dnsmasq_line,
+        SourceFormat::Unbound => parse_unbound_line,
+        SourceFormat::Domains => parse_domain_line,
+    };
+
+    let mut seen = HashSet::new();
+    let mut result = String::new();
+    for (target, domain) in content.lines().flat_map(parse) {
+        if seen.insert(domain.clone()) {
+            result.push_str(target.as_deref().unwrap_or(sink_ip));
+            result.push('\t');
+            result.push_str(&domain);
+            result.push('\n');
+        }
+    }
+    result
+}
+
+/// 根据前若干条有效行判断格式
+fn detect(content: &str) -> SourceFormat {
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+            continue;
+        }
+        if line.starts_with("||") || line.starts_with("@@") || line.starts_with("[Adblock") {
+            return SourceFormat::Adblock;
+        }
+        if line.starts_with("address=/") || line.starts_with("server=/") || line.starts_with("local=/") {
+            return SourceFormat::Dnsmasq;
+        }
+        if line.starts_with("local-zone:") || line.starts_with("local-data:") || line == "server:" {
+            return SourceFormat::Unbound;
+        }
+        let first = line.split_whitespace().next().unwrap_or("");
+        if first.parse::<IpAddr>().is_ok() {
+            return SourceFormat::Hosts;
+        }
+        return SourceFormat::Domains;
+    }
+    SourceFormat::Hosts
+}
+
+/// 规则中的目标地址：不是黑洞地址（空、`0.0.0.0`、`::`、回环地址或无法解析）时返回该地址
+fn redirect_target(value: &str) -> Option<String> {
+    let ip = value.trim().parse::<IpAddr>().ok()?;
+    (!ip.is_unspecified() && !ip.is_loopback()).then(|| ip.to_string())
+}
+
+/// 拦截的域名，指向黑洞地址
+fn blocked(domain: &str) -> Vec<Rule> {
+    normalize_domain(domain).map(|domain| (None, domain)).into_iter().collect()
+}
+
+/// `||example.com^` 或 `||example.com^$important`
+fn parse_adblock_line(line: &str) -> Vec<Rule> {
+    let line = line.trim();
+    let Some(rule) = line.strip_prefix("||") else {
+        return Vec::new();
+    };
+    let (pattern, options) = rule.split_once('$').unwrap_or((rule, ""));
+    // 带其他选项的规则（如只拦截脚本）无法用 Hosts 表达
+    if !options
+        .split(',')
+        .all(|o| matches!(o.trim(), "" | "important" | "all" | "document" | "doc"))
+    {
+        return Vec::new();
+    }
+    let domain = pattern.strip_suffix('^').or_else(|| pattern.strip_suffix("^|")).unwrap_or("");
+    blocked(domain)
+}
+
+/// `address=/a.com/b.com/0.0.0.0`（地址为空或黑洞地址时按拦截处理，其他地址按重定向处理）
+fn parse_dnsmasq_line(line: &str) -> Vec<Rule> {
+    let Some(rest) = line.trim().strip_prefix("address=/") else {
+        return Vec::new();
+    };
+    let mut parts: Vec<&str> = rest.split('/').collect();
+    // 最后一段是地址
+    let target = parts.pop().and_then(redirect_target);
+    parts
+        .into_iter()
+        .filter_map(normalize_domain)
+        .map(|domain| (target.clone(), domain))
+        .collect()
+}
+
+/// `local-zone: "example.com" always_nxdomain` 或 `local-data: "example.com A 0.0.0.0"`，
+/// `local-data` 中的 A/AAAA 记录指向其他地址时按重定向处理
+fn parse_unbound_line(line: &str) -> Vec<Rule> {
+    let line = line.trim();
+    if let Some(rest) = line.strip_prefix("local-zone:") {
+        let mut tokens = rest.split_whitespace();
+        let domain = tokens.next().unwrap_or("").trim_matches('"');
+        let zone_type = tokens.next().unwrap_or("");
+        let blocking = matches!(
+            zone_type,
+            "always_nxdomain" | "always_refuse" | "always_null" | "refuse" | "deny" | "static" | "redirect"
+        );
+        return if blocking {
+            blocked(domain)
+        } else {
+            Vec::new()
+        };
+    }
+    if let Some(rest) = line.strip_prefix("local-data:") {
+        let data = rest.trim().trim_matches('"');
+        let tokens: Vec<&str> = data.split_whitespace().collect();
+        let domain = tokens.first().copied().unwrap_or("");
+        // 记录类型之后是地址，中间可能有 TTL 与 IN
+        let target = tokens
+            .iter()
+            .position(|t| t.eq_ignore_ascii_case("A") || t.eq_ignore_ascii_case("AAAA"))
+            .and_then(|i| tokens.get(i + 1))
+            .and_then(|ip| redirect_target(ip));
+        return normalize_domain(domain).map(|domain| (target, domain)).into_iter().collect();
+    }
+    Vec::new()
+}
+
+/// 每行一个域名，`#` 之后为注释
+fn parse_domain_line(line: &str) -> Vec<Rule> {
+    let line = line.split('#').next().unwrap_or("").trim();
+    if line.starts_with('!') {
+        return Vec::new();
+    }
+    blocked(line)
+}
+
+fn normalize_domain(domain: &str) -> Option<String> {
+    let domain = domain.trim().trim_end_matches('.').to_lowercase();
+    let valid = !domain.is_empty()
+        && domain.contains('.')
+        && domain.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    valid.then_some(domain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adblock_rules() {
+        let content = "[Adblock Plus 2.0]\n\
+                       ! Title: test\n\
+                       ||ads.example.com^\n\
+                       ||Tracker.Example.net^$important\n\
+                       ||cdn.example.org^$script\n\
+                       @@||good.example.com^\n\
+                       example.com##.banner\n\
+                       ||ads.example.com^\n\
+                       ||*.wild.example^\n";
+        assert_eq!(
+            to_hosts(content, SourceFormat::Adblock, "0.0.0.0"),
+            "0.0.0.0\tads.example.com\n0.0.0.0\ttracker.example.net\n"
+        );
+        assert_eq!(detect(content), SourceFormat::Adblock);
+    }
+
+    #[test]
+    fn test_dnsmasq_unbound_and_domain_lists() {
+        let dnsmasq = "# dnsmasq\naddress=/ads.example.com/0.0.0.0\naddress=/a.example/b.example/\nserver=/corp.example/10.0.0.1\n";
+        assert_eq!(
+            to_hosts(dnsmasq, SourceFormat::Auto, "127.0.0.1"),
+            "127.0.0.1\tads.example.com\n127.0.0.1\ta.example\n127.0.0.1\tb.example\n"
+        );
+
+        let unbound = "server:\n  local-zone: \"ads.example.com\" always_nxdomain\n  local-zone: \"ok.example\" transparent\n  local-data: \"t.example A 0.0.0.0\"\n";
+        assert_eq!(
+            to_hosts(unbound, SourceFormat::Auto, "0.0.0.0"),
+            "0.0.0.0\tads.example.com\n0.0.0.0\tt.example\n"
+        );
+
+        let domains = "# list\nads.example.com\ntracker.example.net # comment\nlocalhost\n";
+        assert_eq!(
+            to_hosts(domains, SourceFormat::Auto, "::"),
+            "::\tads.example.com\n::\ttracker.example.net\n"
+        );
+
+        let hosts = "0.0.0.0 ads.example.com\n";
+        assert_eq!(to_hosts(hosts, SourceFormat::Auto, "0.0.0.0"), hosts);
+        assert!(sink_ip(Some("not-ip")).is_err());
+        assert_eq!(sink_ip(None).unwrap(), "0.0.0.0");
+    }
+
+    #[test]
+    fn test_redirects_keep_target_address() {
+        let dnsmasq = "address=/nas.home.example/192.168.1.10
+                       address=/v6.example/2001:db8::1
+                       address=/ads.example.com/127.0.0.1
+                       address=/null.example/::
+                       address=/nx.example/#
+";
+        assert_eq!(
+            to_hosts(dnsmasq, SourceFormat::Dnsmasq, "0.0.0.0"),
+            "192.168.1.10\tnas.home.example\n\
+             2001:db8::1\tv6.example\n\
+             0.0.0.0\tads.example.com\n\
+             0.0.0.0\tnull.example\n\
+             0.0.0.0\tnx.example\n"
+        );
+
+        let unbound = "local-data: \"git.corp.example. 3600 IN A 10.1.2.3\"\n\
+                       local-data: \"v6.corp.example AAAA 2001:db8::2\"\n\
+                       local-data: \"t.example A 0.0.0.0\"\n\
+                       local-data: \"txt.example TXT hello\"\n";
+        assert_eq!(
+            to_hosts(unbound, SourceFormat::Unbound, "0.0.0.0"),
+            "10.1.2.3\tgit.corp.example\n\
+             2001:db8::2\tv6.corp.example\n\
+             0.0.0.0\tt.example\n\
+             0.0.0.0\ttxt.example\n"
+        );
+    }
+}
@@ -0,0 +1,149 @@
+//! Hosts 分组与受管区块
+//!
+//! 应用维护的 hosts 内容（分组等）统一写在一对标记注释之间：
+//!
+//! ```text
+//! # >>> IP Switch managed hosts >>>
+//! # --- group: dev ---
+//! 10.0.0.5    api.dev
+//! # <<< IP Switch managed hosts <<<
+//! ```
+//!
+//! 标记之外的内容（系统默认内容与用户手工编辑）在重新生成时保持不变。
+
+use serde::{Deserialize, Serialize};
+
+pub const BEGIN_MARKER: &str = "# >>> IP Switch managed hosts >>>";
+pub const END_MARKER: &str = "# <<< IP Switch managed hosts <<<";
+
+/// 用户定义的 hosts 分组，可单独启用/禁用
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HostsGroup {
+    pub name: String,
+    pub enabled: bool,
+    pub content: String,
+}
+
+/// 受管区块中的一段内容
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManagedSection {
+    pub title: String,
+    pub content: String,
+}
+
+impl ManagedSection {
+    pub fn group(group: &HostsGroup) -> Self {
+        Self {
+            title: format!("group: {}", group.name),
+            content: group.content.clone(),
+        }
+    }
+}
+
+/// 找到受管区块的位置：(区块起始字节, 区块结束字节（含结束标记所在行的换行符）)
+fn find_block(content: &str) -> Option<(usize, usize)> {
+    let mut offset = 0;
+    let mut begin = None;
+    for line in content.split_inclusive('\n') {
+        let text = line.trim();
+        match begin {
+            None if text == BEGIN_MARKER => begin = Some(offset),
+            Some(start) if text == END_MARKER => return Some((start, offset + line.len())),
+            _ => {}
+        }
+        offset += line.len();
+    }
+    // 只有开始标记没有结束标记时不视为受管区块，避免误删用户内容
+    None
+}
+
+/// 用给定内容重新生成受管区块：已有区块原位替换，否则追加到末尾；
+/// 没有任何内容时移除区块
+pub fn apply_managed_block(content: &str, sections: &[ManagedSection]) -> String {
+    let newline = if content.contains("\r\n") || !content.contains('\n') {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let block = render_block(sections, newline);
+
+    match find_block(content) {
+        Some((start, end)) => format!("{}{}{}", &content[..start], block, &content[end..]),
+        None if block.is_empty() => content.to_string(),
+        None => {
+            let mut result = content.to_string();
+            if !result.is_empty() && !result.ends_with('\n') {
+                result.push_str(newline);
+            }
+            result.push_str(&block);
+            result
+        }
+    }
+}
+
+fn render_block(sections: &[ManagedSection], newline: &str) -> String {
+    if sections.is_empty() {
+        return String::new();
+    }
+    let mut block = String::new();
+    block.push_str(BEGIN_MARKER);
+    block.push_str(newline);
+    for section in sections {
+        block.push_str(&format!("# --- {} ---{}", section.title, newline));
+        for line in section.content.lines() {
+            // 内容中不允许再出现标记行，避免区块嵌套
+            let text = line.trim_end_matches('\r');
+            if text.trim() == BEGIN_MARKER || text.trim() == END_MARKER {
+                continue;
+            }
+            block.push_str(text);
+            block.push_str(newline);
+        }
+    }
+    block.push_str(END_MARKER);
+    block.push_str(newline);
+    block
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn section(title: &str, content: &str) -> ManagedSection {
+        ManagedSection {
+            title: title.to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_block_is_appended_then_replaced_in_place() {
+        let base = "127.0.0.1 localhost\r\n# my notes";
+        let first = apply_managed_block(base, &[section("group: dev", "10.0.0.5 api.dev")]);
+        assert_eq!(
+            first,
+            "127.0.0.1 localhost\r\n# my notes\r\n# >>> IP Switch managed hosts >>>\r\n# --- group: dev ---\r\n10.0.0.5 api.dev\r\n# <<< IP Switch managed hosts <<<\r\n"
+        );
+
+        // 用户在区块前后追加的内容保持不变
+        let edited = format!("{}1.2.3.4 mine\r\n", first.replace("# my notes", "# my notes v2"));
+        let second = apply_managed_block(&edited, &[section("group: ads", "0.0.0.0 ads.example")]);
+        assert!(second.starts_with("127.0.0.1 localhost\r\n# my notes v2\r\n# >>> IP Switch"));
+        assert!(second.contains("0.0.0.0 ads.example\r\n"));
+        assert!(!second.contains("api.dev"));
+        assert!(second.ends_with("# <<< IP Switch managed hosts <<<\r\n1.2.3.4 mine\r\n"));
+
+        assert_eq!(
+            apply_managed_block(&second, &[]),
+            "127.0.0.1 localhost\r\n# my notes v2\r\n1.2.3.4 mine\r\n"
+        );
+    }
+
+    #[test]
+    fn test_unterminated_block_is_left_alone() {
+        let content = "a\n# >>> IP Switch managed hosts >>>\nb\n";
+        assert_eq!(apply_managed_block(content, &[]), content);
+        let content = "a\n# >>> IP Switch managed hosts >>>\nb\n# <<< IP Switch managed hosts <<<\nc\n";
+        assert_eq!(apply_managed_block(content, &[]), "a\nc\n");
+    }
+}
@@ -0,0 +1,351 @@
+//! Hosts 检查与整理
+//!
+//! 检查基于语法树，只考虑启用的条目。Windows 解析 Hosts 时，同一主机名在同一地址族
+//! （IPv4 / IPv6）中以第一条映射为准，之后的映射不会生效。
+
+use super::groups::{BEGIN_MARKER, END_MARKER};
+use super::parser::{format_entry, EntryData, HostsDocument, LineKind};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::net::IpAddr;
+
+/// 超过该长度的行可能被部分解析器截断
+pub const MAX_LINE_LENGTH: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LintKind {
+    /// 同一主机名重复映射到同一 IP
+    Duplicate,
+    /// 同一主机名映射到不同 IP，后面的映射不会生效
+    Conflict,
+    InvalidIp,
+    OverlongLine,
+    /// 整行的主机名都已在前面映射过，该行完全不会生效
+    Shadowed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LintIssue {
+    /// 所在行号，从 0 开始（与 `HostsEntry::line` 一致）
+    pub line: usize,
+    pub kind: LintKind,
+    pub hostname: Option<String>,
+    /// 与之重复或冲突的、较早的行
+    pub related_line: Option<usize>,
+    pub message: String,
+}
+
+/// 整理结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NormalizeReport {
+    /// 删除的重复映射数量（按主机名计）
+    pub removed_mappings: usize,
+    /// 整理后仍然存在的问题（如冲突需要人工处理）
+    pub remaining_issues: Vec<LintIssue>,
+}
+
+pub fn lint(doc: &HostsDocument) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+    // (小写主机名, 是否 IPv6) -> (首次出现的行, IP)
+    let mut first_seen: HashMap<(String, bool), (usize, IpAddr)> = HashMap::new();
+
+    for (index, line) in doc.lines.iter().enumerate() {
+        let length = line.text().chars().count();
+        if length > MAX_LINE_LENGTH {
+            issues.push(LintIssue {
+                line: index,
+                kind: LintKind::OverlongLine,
+                hostname: None,
+                related_line: None,
+                message: format!("行长度 {} 超过 {} 个字符", length, MAX_LINE_LENGTH),
+            });
+        }
+
+        let LineKind::Entry(data) = &line.kind else {
+            continue;
+        };
+        if !data.enabled {
+            continue;
+        }
+        let Ok(ip) = data.ip.parse::<IpAddr>() else {
+            issues.push(LintIssue {
+                line: index,
+                kind: LintKind::InvalidIp,
+                hostname: None,
+                related_line: None,
+                message: format!("无效的 IP 地址: {}", data.ip),
+            });
+            continue;
+        };
+
+        let mut effective = 0;
+        for name in &data.hostnames {
+            let key = (name.to_lowercase(), ip.is_ipv6());
+            match first_seen.get(&key) {
+                None => {
+                    first_seen.insert(key, (index, ip));
+                    effective += 1;
+                }
+                Some(&(first, first_ip)) if first_ip == ip => issues.push(LintIssue {
+                    line: index,
+                    kind: LintKind::Duplicate,
+                    hostname: Some(name.clone()),
+                    related_line: Some(first),
+                    message: format!("{} -> {} 与第 {} 行重复", name, ip, first + 1),
+                }),
+                Some(&(first, first_ip)) => issues.push(LintIssue {
+                    line: index,
+                    kind: LintKind::Conflict,
+                    hostname: Some(name.clone()),
+                    related_line: Some(first),
+                    message: format!(
+                        "{} 已在第 {} 行映射到 {}，此处的 {} 不会生效",
+                        name,
+                        first + 1,
+                        first_ip,
+                        ip
+                    ),
+                }),
+            }
+        }
+
+        if effective == 0 {
+            issues.push(LintIssue {
+                line: index,
+                kind: LintKind::Shadowed,
+                hostname: None,
+                related_line: None,
+                message: "该行的主机名都已在前面映射过，整行不会生效".to_string(),
+            });
+        }
+    }
+
+    issues
+}
+
+/// 一组连续条目中的一行
+struct GroupLine {
+    /// (小写的首个主机名, IP)
+    sort_key: (String, String),
+    /// 该行生效的映射：(小写主机名, 是否 IPv6)
+    names: Vec<(String, bool)>,
+    text: String,
+}
+
+/// 按排序键排列一组条目。同一地址族中映射相同主机名的行保持原有先后顺序，
+/// 因此排序不会改变哪一条映射生效
+fn sort_group(group: &mut Vec<GroupLine>, output: &mut Vec<String>) {
+    // 每行依赖于之前最后一个映射相同主机名的行
+    let mut last: HashMap<&(String, bool), usize> = HashMap::new();
+    let mut pending = vec![0usize; group.len()];
+    let mut followers: Vec<Vec<usize>> = vec![Vec::new(); group.len()];
+    for (index, line) in group.iter().enumerate() {
+        let mut before: Vec<usize> = line.names.iter().filter_map(|n| last.insert(n, index)).collect();
+        before.sort_unstable();
+        before.dedup();
+        pending[index] = before.len();
+        for b in before {
+            followers[b].push(index);
+        }
+    }
+
+    let mut ready: BinaryHeap<Reverse<(&(String, String), usize)>> = group
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| pending[*index] == 0)
+        .map(|(index, line)| Reverse((&line.sort_key, index)))
+        .collect();
+    let mut order = Vec::with_capacity(group.len());
+    while let Some(Reverse((_, index))) = ready.pop() {
+        order.push(index);
+        for &next in &followers[index] {
+            pending[next] -= 1;
+            if pending[next] == 0 {
+                ready.push(Reverse((&group[next].sort_key, next)));
+            }
+        }
+    }
+
+    let mut texts: Vec<Option<String>> = group.drain(..).map(|line| Some(line.text)).collect();
+    output.extend(order.into_iter().filter_map(|index| texts[index].take()));
+}
+
+/// 整理：删除重复的映射（同一主机名与 IP），并在每一组连续的条目内按主机名排序，
+/// 排序不会改变主机名的解析结果。受管区块由应用生成，保持不变，但其中的映射参与去重判断。
+/// 返回整理后的内容与删除的映射数量。
+pub fn normalize(doc: &HostsDocument) -> (String, usize) {
+    let mut seen: HashSet<(String, IpAddr)> = HashSet::new();
+    let mut output: Vec<String> = Vec::new();
+    // 当前这一组连续条目
+    let mut group: Vec<GroupLine> = Vec::new();
+    let mut removed = 0;
+    let mut in_block = false;
+
+    for line in &doc.lines {
+        let text = line.text();
+        let trimmed = text.trim();
+
+        if in_block || trimmed == BEGIN_MARKER {
+            sort_group(&mut group, &mut output);
+            in_block = trimmed != END_MARKER;
+            if let LineKind::Entry(data) = &line.kind {
+                if let (true, Ok(ip)) = (data.enabled, data.ip.parse::<IpAddr>()) {
+                    seen.extend(data.hostnames.iter().map(|h| (h.to_lowercase(), ip)));
+                }
+            }
+            output.push(text.to_string());
+            continue;
+        }
+
+        let LineKind::Entry(data) = &line.kind else {
+            sort_group(&mut group, &mut output);
+            output.push(text.to_string());
+            continue;
+        };
+
+        let sort_key = |hostnames: &[String]| (hostnames[0].to_lowercase(), data.ip.clone());
+        let ip = match data.ip.parse::<IpAddr>() {
+            Ok(ip) if data.enabled => ip,
+            // 禁用或 IP 无效的条目不生效，只参与排序
+            _ => {
+                group.push(GroupLine {
+                    sort_key: sort_key(&data.hostnames),
+                    names: Vec::new(),
+                    text: text.to_string(),
+                });
+                continue;
+            }
+        };
+
+        let hostnames: Vec<String> = data
+            .hostnames
+            .iter()
+            .filter(|h| seen.insert((h.to_lowercase(), ip)))
+            .cloned()
+            .collect();
+        removed += data.hostnames.len() - hostnames.len();
+
+        if hostnames.is_empty() {
+            continue;
+        }
+        let sort_key = sort_key(&hostnames);
+        let names = hostnames.iter().map(|h| (h.to_lowercase(), ip.is_ipv6())).collect();
+        let text = if hostnames.len() == data.hostnames.len() {
+            text.to_string()
+        } else {
+            format_entry(&EntryData {
+                hostnames,
+                ..data.clone()
+            })
+        };
+        group.push(GroupLine { sort_key, names, text });
+    }
+    sort_group(&mut group, &mut output);
+
+    let newline = doc.newline();
+    let mut content = output.join(newline);
+    if doc.render().ends_with('\n') {
+        content.push_str(newline);
+    }
+    (content, removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "# dev\n\
+                          10.0.0.2 b.dev\n\
+                          10.0.0.1 a.dev a.dev\n\
+                          10.0.0.9 b.dev\n\
+                          \n\
+                          # staging\n\
+                          10.0.0.1 A.dev c.dev\n\
+                          10.0.0.9 b.dev\n\
+                          999.0.0.1 x.dev\n\
+                          ::1 b.dev\n";
+
+    #[test]
+    fn test_lint_reports_issues() {
+        let long = format!("1.1.1.1 {}\n", "a".repeat(MAX_LINE_LENGTH));
+        let doc = HostsDocument::parse(&format!("{}{}", SAMPLE, long));
+        let issues: Vec<(usize, LintKind, Option<usize>)> = lint(&doc)
+            .iter()
+            .map(|i| (i.line, i.kind, i.related_line))
+            .collect();
+        assert_eq!(
+            issues,
+            vec![
+                (2, LintKind::Duplicate, Some(2)),
+                (3, LintKind::Conflict, Some(1)),
+                (3, LintKind::Shadowed, None),
+                (6, LintKind::Duplicate, Some(2)),
+                (7, LintKind::Conflict, Some(1)),
+                (7, LintKind::Shadowed, None),
+                (8, LintKind::InvalidIp, None),
+                (10, LintKind::OverlongLine, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_normalize_dedupes_and_sorts_within_groups() {
+        let managed = format!("{}\n10.0.0.3 c.dev\n{}\n10.0.0.3 c.dev\n", BEGIN_MARKER, END_MARKER);
+        let doc = HostsDocument::parse(&format!("{}{}", SAMPLE, managed));
+        let (content, removed) = normalize(&doc);
+        assert_eq!(removed, 4);
+        assert_eq!(
+            content,
+            format!(
+                "# dev\n\
+                 10.0.0.1\ta.dev\n\
+                 10.0.0.2 b.dev\n\
+                 10.0.0.9 b.dev\n\
+                 \n\
+                 # staging\n\
+                 ::1 b.dev\n\
+                 10.0.0.1\tc.dev\n\
+                 999.0.0.1 x.dev\n\
+                 {}\n\
+                 10.0.0.3 c.dev\n\
+                 {}\n",
+                BEGIN_MARKER, END_MARKER
+            )
+        );
+    }
+
+    #[test]
+    fn test_normalize_keeps_first_mapping() {
+        let doc = HostsDocument::parse(
+            "10.0.0.2 x.dev\n\
+             10.0.0.1 x.dev\n\
+             10.0.0.5 b.dev y.dev\n\
+             10.0.0.3 a.dev y.dev\n\
+             ::1 x.dev\n",
+        );
+        // 整理前后每个主机名生效的映射相同：冲突与被覆盖的行一致
+        let conflicts = |doc: &HostsDocument| {
+            let mut issues: Vec<(Option<String>, String)> = lint(doc)
+                .iter()
+                .map(|i| (i.hostname.clone(), doc.lines[i.line].text().to_string()))
+                .collect();
+            issues.sort();
+            issues
+        };
+        let before = conflicts(&doc);
+        let (content, removed) = normalize(&doc);
+        assert_eq!(removed, 0);
+        // 映射相同主机名的行保持原有顺序，其余的行照常排序
+        assert_eq!(
+            content,
+            "10.0.0.5 b.dev y.dev\n\
+             10.0.0.3 a.dev y.dev\n\
+             10.0.0.2 x.dev\n\
+             10.0.0.1 x.dev\n\
+             ::1 x.dev\n"
+        );
+        assert_eq!(conflicts(&HostsDocument::parse(&content)), before);
+    }
+}
@@ -0,0 +1,336 @@
+//! Hosts 文件语法树
+//!
+//! 按行解析，保留注释、空行与顺序；未修改的行原样（逐字节）写回，
+//! 只有被修改或新增的行才会重新生成。被注释掉的映射（如 `# 127.0.0.1 foo`）
+//! 视为“已禁用”的条目。
+
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+
+/// 对外暴露的条目（`line` 为所在行号，从 0 开始，用于后续修改操作）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HostsEntry {
+    pub line: usize,
+    pub ip: String,
+    pub hostnames: Vec<String>,
+    pub comment: Option<String>,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineKind {
+    Blank,
+    Comment,
+    Entry(EntryData),
+    /// 无法识别的行（如只有 IP 没有主机名），原样保留
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryData {
+    pub ip: String,
+    pub hostnames: Vec<String>,
+    pub comment: Option<String>,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostsLine {
+    /// 原始文本（包含行尾换行符）
+    raw: String,
+    pub kind: LineKind,
+}
+
+impl HostsLine {
+    /// 不含换行符的行内容
+    pub fn text(&self) -> &str {
+        self.raw.trim_end_matches(['\r', '\n'])
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostsDocument {
+    pub lines: Vec<HostsLine>,
+    /// 新生成行使用的换行符（沿用文件中第一处换行符）
+    newline: String,
+}
+
+impl HostsDocument {
+    pub fn parse(content: &str) -> Self {
+        let newline = if content.contains("\r\n") || !content.contains('\n') {
+            "\r\n"
+        } else {
+            "\n"
+        }
+        .to_string();
+
+        let lines = content
+            .split_inclusive('\n')
+            .map(|raw| HostsLine {
+                raw: raw.to_string(),
+                kind: parse_line(raw.trim_end_matches(['\r', '\n'])),
+            })
+            .collect();
+
+        Self { lines, newline }
+    }
+
+    pub fn render(&self) -> String {
+        self.lines.iter().map(|l| l.raw.as_str()).collect()
+    }
+
+    pub fn newline(&self) -> &str {
+        &self.newline
+    }
+
+    pub fn entries(&self) -> Vec<HostsEntry> {
+        self.lines
+            .iter()
+            .enumerate()
+            .filter_map(|(line, l)| match &l.kind {
+                LineKind::Entry(data) => Some(HostsEntry {
+                    line,
+                    ip: data.ip.clone(),
+                    hostnames: data.hostnames.clone(),
+                    comment: data.comment.clone(),
+                    enabled: data.enabled,
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// 在文件末尾追加条目，返回新条目的行号
+    pub fn add_entry(
+        &mut self,
+        ip: &str,
+        hostnames: &[String],
+        comment: Option<&str>,
+        enabled: bool,
+    ) -> Result<usize, String> {
+        let data = validate_entry(ip, hostnames, comment, enabled)?;
+        self.ensure_trailing_newline();
+        let raw = format!("{}{}", format_entry(&data), self.newline);
+        self.lines.push(HostsLine {
+            raw,
+            kind: LineKind::Entry(data),
+        });
+        Ok(self.lines.len() - 1)
+    }
+
+    pub fn remove_entry(&mut self, line: usize) -> Result<(), String> {
+        self.entry_at(line)?;
+        self.lines.remove(line);
+        Ok(())
+    }
+
+    pub fn set_enabled(&mut self, line: usize, enabled: bool) -> Result<(), String> {
+        let mut data = self.entry_at(line)?.clone();
+        if data.enabled == enabled {
+            return Ok(());
+        }
+        data.enabled = enabled;
+        self.replace_entry(line, data);
+        Ok(())
+    }
+
+    pub fn update_entry(
+        &mut self,
+        line: usize,
+        ip: &str,
+        hostnames: &[String],
+        comment: Option<&str>,
+    ) -> Result<(), String> {
+        let enabled = self.entry_at(line)?.enabled;
+        let data = validate_entry(ip, hostnames, comment, enabled)?;
+        self.replace_entry(line, data);
+        Ok(())
+    }
+
+    fn entry_at(&self, line: usize) -> Result<&EntryData, String> {
+        match self.lines.get(line).map(|l| &l.kind) {
+            Some(LineKind::Entry(data)) => Ok(data),
+            Some(_) => Err(format!("第 {} 行不是 Hosts 条目", line + 1)),
+            None => Err(format!("第 {} 行不存在", line + 1)),
+        }
+    }
+
+    /// 替换条目内容，保留该行原有的换行符
+    fn replace_entry(&mut self, line: usize, data: EntryData) {
+        let old = &self.lines[line];
+        let ending = &old.raw[old.text().len()..];
+        let raw = format!("{}{}", format_entry(&data), ending);
+        self.lines[line] = HostsLine {
+            raw,
+            kind: LineKind::Entry(data),
+        };
+    }
+
+    fn ensure_trailing_newline(&mut self) {
+        let newline = self.newline.clone();
+        if let Some(last) = self.lines.last_mut() {
+            if !last.raw.ends_with('\n') {
+                last.raw.push_str(&newline);
+            }
+        }
+    }
+}
+
+/// 生成条目文本：`IP<TAB>host1 host2 # comment`，禁用时整行加 `# `
+pub fn format_entry(data: &EntryData) -> String {
+    let mut text = format!("{}\t{}", data.ip, data.hostnames.join(" "));
+    if let Some(comment) = data.comment.as_deref().filter(|c| !c.is_empty()) {
+        text.push_str(" # ");
+        text.push_str(comment);
+    }
+    if data.enabled {
+        text
+    } else {
+        format!("# {}", text)
+    }
+}
+
+fn validate_entry(
+    ip: &str,
+    hostnames: &[String],
+    comment: Option<&str>,
+    enabled: bool,
+) -> Result<EntryData, String> {
+    let ip = ip.trim();
+    ip.parse::<IpAddr>()
+        .map_err(|_| format!("无效的 IP 地址: {}", ip))?;
+
+    let hostnames: Vec<String> = hostnames
+        .iter()
+        .map(|h| h.trim().to_string())
+        .filter(|h| !h.is_empty())
+        .collect();
+    if hostnames.is_empty() {
+        return Err("至少需要一个主机名".to_string());
+    }
+    if let Some(bad) = hostnames
+        .iter()
+        .find(|h| h.contains('#') || h.chars().any(char::is_whitespace))
+    {
+        return Err(format!("无效的主机名: {}", bad));
+    }
+
+    let comment = comment
+        .map(|c| c.trim().replace(['\r', '\n'], " "))
+        .filter(|c| !c.is_empty());
+
+    Ok(EntryData {
+        ip: ip.to_string(),
+        hostnames,
+        comment,
+        enabled,
+    })
+}
+
+fn parse_line(text: &str) -> LineKind {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return LineKind::Blank;
+    }
+
+    if let Some(rest) = trimmed.strip_prefix('#') {
+        // 被注释掉的映射：# 后面是合法 IP + 主机名时视为禁用的条目
+        return match parse_mapping(rest.trim_start_matches('#')) {
+            Some(data) if data.ip.parse::<IpAddr>().is_ok() => LineKind::Entry(EntryData {
+                enabled: false,
+                ..data
+            }),
+            _ => LineKind::Comment,
+        };
+    }
+
+    // 启用的行即使 IP 不合法也当作条目，方便检查工具报告
+    match parse_mapping(trimmed) {
+        Some(data) => LineKind::Entry(data),
+        None => LineKind::Other,
+    }
+}
+
+fn parse_mapping(text: &str) -> Option<EntryData> {
+    let (body, comment) = match text.split_once('#') {
+        Some((body, comment)) => (body, Some(comment.trim().to_string()).filter(|c| !c.is_empty())),
+        None => (text, None),
+    };
+    let mut tokens = body.split_whitespace();
+    let ip = tokens.next()?.to_string();
+    let hostnames: Vec<String> = tokens.map(|t| t.to_string()).collect();
+    if hostnames.is_empty() {
+        return None;
+    }
+    Some(EntryData {
+        ip,
+        hostnames,
+        comment,
+        enabled: true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "# Copyright (c) 1993-2009 Microsoft Corp.\r\n#\r\n#      102.54.94.97     rhino.acme.com          # source server\r\n\r\n127.0.0.1  localhost\r\n10.0.0.5\tgit.corp  build.corp   # CI\r\n   \r\n0.0.0.0 ads.example\r\nnot-a-line\r\n# 192.168.1.1 router.lan";
+
+    #[test]
+    fn test_untouched_round_trip_is_byte_for_byte() {
+        let doc = HostsDocument::parse(SAMPLE);
+        assert_eq!(doc.render(), SAMPLE);
+
+        let lf = SAMPLE.replace("\r\n", "\n");
+        assert_eq!(HostsDocument::parse(&lf).render(), lf);
+        assert_eq!(HostsDocument::parse("").render(), "");
+    }
+
+    #[test]
+    fn test_entries_include_disabled_mappings() {
+        let doc = HostsDocument::parse(SAMPLE);
+        let entries = doc.entries();
+        let summary: Vec<(usize, &str, bool)> =
+            entries.iter().map(|e| (e.line, e.ip.as_str(), e.enabled)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (2, "102.54.94.97", false),
+                (4, "127.0.0.1", true),
+                (5, "10.0.0.5", true),
+                (7, "0.0.0.0", true),
+                (9, "192.168.1.1", false),
+            ]
+        );
+        assert_eq!(entries[2].hostnames, vec!["git.corp", "build.corp"]);
+        assert_eq!(entries[2].comment.as_deref(), Some("CI"));
+        assert_eq!(doc.lines[8].kind, LineKind::Other);
+    }
+
+    #[test]
+    fn test_edits_only_touch_modified_lines() {
+        let mut doc = HostsDocument::parse(SAMPLE);
+        doc.set_enabled(7, false).unwrap();
+        doc.set_enabled(9, true).unwrap();
+        doc.update_entry(5, "10.0.0.6", &["git.corp".to_string()], None).unwrap();
+        let line = doc.add_entry("::1", &["ip6.local".to_string()], Some("v6"), true).unwrap();
+        doc.remove_entry(4).unwrap();
+
+        assert_eq!(line, 10);
+        assert_eq!(
+            doc.render(),
+            "# Copyright (c) 1993-2009 Microsoft Corp.\r\n#\r\n#      102.54.94.97     rhino.acme.com          # source server\r\n\r\n10.0.0.6\tgit.corp\r\n   \r\n# 0.0.0.0\tads.example\r\nnot-a-line\r\n192.168.1.1\trouter.lan\r\n::1\tip6.local # v6\r\n"
+        );
+    }
+
+    #[test]
+    fn test_invalid_edits_are_rejected() {
+        let mut doc = HostsDocument::parse(SAMPLE);
+        assert!(doc.add_entry("999.1.1.1", &["x".to_string()], None, true).is_err());
+        assert!(doc.add_entry("1.1.1.1", &[], None, true).is_err());
+        assert!(doc.add_entry("1.1.1.1", &["a#b".to_string()], None, true).is_err());
+        assert!(doc.remove_entry(0).is_err());
+        assert!(doc.set_enabled(100, true).is_err());
+        assert_eq!(doc.render(), SAMPLE);
+    }
+}
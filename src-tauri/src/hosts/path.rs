@@ -0,0 +1,131 @@
+//! Hosts 文件路径
+//!
+//! 解析顺序：手动指定的路径（保存在应用设置中）→ 系统路径。Windows 下系统路径来自注册表
+//! `HKLM\SYSTEM\CurrentControlSet\Services\Tcpip\Parameters\DataBasePath`，
+//! 其他系统为 `/etc/hosts`。手动指定可用于编辑挂载镜像中的 Hosts 文件或测试。
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{OnceLock, RwLock};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HostsPathSource {
+    /// 在应用中手动指定
+    Override,
+    /// 系统路径（Windows 注册表或 /etc/hosts）
+    System,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HostsPathInfo {
+    pub path: String,
+    pub source: HostsPathSource,
+    pub exists: bool,
+}
+
+fn override_lock() -> &'static RwLock<Option<PathBuf>> {
+    static INSTANCE: OnceLock<RwLock<Option<PathBuf>>> = OnceLock::new();
+    INSTANCE.get_or_init(|| RwLock::new(None))
+}
+
+/// 设置（或清除）手动指定的路径
+pub fn set_override(path: Option<PathBuf>) {
+    if let Ok(mut guard) = override_lock().write() {
+        *guard = path;
+    }
+}
+
+/// 当前使用的 Hosts 路径及其来源
+pub fn resolve() -> (PathBuf, HostsPathSource) {
+    if let Some(path) = override_lock().read().ok().and_then(|g| g.clone()) {
+        return (path, HostsPathSource::Override);
+    }
+    (system_hosts_path(), HostsPathSource::System)
+}
+
+/// 系统 Hosts 路径
+#[cfg(target_os = "windows")]
+pub fn system_hosts_path() -> PathBuf {
+    use winreg::enums::HKEY_LOCAL_MACHINE;
+    use winreg::RegKey;
+
+    let from_registry = RegKey::predef(HKEY_LOCAL_MACHINE)
+        .open_subkey(r"SYSTEM\CurrentControlSet\Services\Tcpip\Parameters")
+        .and_then(|key| key.get_value::<String, _>("DataBasePath"))
+        .ok()
+        .map(|value| expand_env_vars(&value, |name| std::env::var(name).ok()))
+        .filter(|value| !value.trim().is_empty());
+
+    let dir = from_registry.map(PathBuf::from).unwrap_or_else(|| {
+        let root = std::env::var("SystemRoot").unwrap_or_else(|_| r"C:\Windows".to_string());
+        PathBuf::from(root).join(r"System32\drivers\etc")
+    });
+    dir.join("hosts")
+}
+
+/// 系统 Hosts 路径
+#[cfg(not(target_os = "windows"))]
+pub fn system_hosts_path() -> PathBuf {
+    PathBuf::from("/etc/hosts")
+}
+
+/// 展开 `%VAR%` 形式的环境变量（注册表中 REG_EXPAND_SZ 的值不会自动展开），
+/// 未定义的变量保持原样
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn expand_env_vars(value: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut result = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find('%') {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('%') {
+            Some(end) if end > 0 => {
+                let name = &after[..end];
+                match lookup(name) {
+                    Some(expanded) => result.push_str(&expanded),
+                    None => {
+                        result.push('%');
+                        result.push_str(name);
+                        result.push('%');
+                    }
+                }
+                rest = &after[end + 1..];
+            }
+            _ => {
+                result.push('%');
+                rest = after;
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_env_vars() {
+        let lookup = |name: &str| (name.eq_ignore_ascii_case("SystemRoot")).then(|| r"D:\Win".to_string());
+        assert_eq!(
+            expand_env_vars(r"%SystemRoot%\System32\drivers\etc", lookup),
+            r"D:\Win\System32\drivers\etc"
+        );
+        assert_eq!(expand_env_vars(r"%UNKNOWN%\x", lookup), r"%UNKNOWN%\x");
+        assert_eq!(expand_env_vars("100% %% done%", lookup), "100% %% done%");
+    }
+
+    #[test]
+    fn test_resolve_prefers_override() {
+        assert_eq!(resolve(), (system_hosts_path(), HostsPathSource::System));
+
+        let custom = std::env::temp_dir().join("mounted-image").join("hosts");
+        set_override(Some(custom.clone()));
+        assert_eq!(resolve(), (custom, HostsPathSource::Override));
+
+        set_override(None);
+        assert_eq!(resolve(), (system_hosts_path(), HostsPathSource::System));
+    }
+}
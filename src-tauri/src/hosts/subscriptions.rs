@@ -0,0 +1,213 @@
+//! 远程 Hosts 订阅
+//!
+//! 订阅由后端统一维护：每个订阅有独立的刷新间隔，请求时带上 ETag /
+//! Last-Modified 做条件请求。下载的内容缓存在应用数据目录中，作为受管区块中的
+//! 一段写入 Hosts 文件，每次刷新整体替换上一次的内容，不再不断追加。
+
+use super::convert::{self, SourceFormat};
+use super::groups::ManagedSection;
+use super::parser::HostsDocument;
+use super::validate::ValidationReport;
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+/// 默认刷新间隔：12 小时
+pub const DEFAULT_INTERVAL_MINUTES: u64 = 720;
+/// 最短刷新间隔，同时作为刷新失败后的重试间隔
+pub const MIN_INTERVAL_MINUTES: u64 = 10;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HostsSubscription {
+    pub name: String,
+    pub url: String,
+    pub enabled: bool,
+    pub interval_minutes: u64,
+    /// 远程内容的格式（Hosts、Adblock、dnsmasq 等）
+    #[serde(default)]
+    pub format: SourceFormat,
+    /// 非 Hosts 格式转换时被拦截域名指向的地址，默认 0.0.0.0
+    #[serde(default)]
+    pub sink_ip: Option<String>,
+    #[serde(default)]
+    pub etag: Option<String>,
+    #[serde(default)]
+    pub last_modified: Option<String>,
+    /// 上次检查时间（无论成功与否）
+    #[serde(default)]
+    pub last_checked: Option<u64>,
+    #[serde(default)]
+    pub last_success: Option<u64>,
+    /// 最近一次成功下载的内容中启用的条目数
+    #[serde(default)]
+    pub entry_count: usize,
+    #[serde(default)]
+    pub last_error: Option<String>,
+    /// 最近一次校验的结果（被拒绝的行）
+    #[serde(default)]
+    pub report: ValidationReport,
+}
+
+/// 一次下载的结果
+#[derive(Debug)]
+pub enum FetchOutcome {
+    /// 服务器返回 304，沿用缓存内容
+    NotModified,
+    Updated {
+        content: String,
+        etag: Option<String>,
+        last_modified: Option<String>,
+    },
+}
+
+impl HostsSubscription {
+    /// 是否到了刷新时间；上次失败时按最短间隔重试
+    pub fn is_due(&self, now: u64) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        let Some(last) = self.last_checked else {
+            return true;
+        };
+        let minutes = if self.last_error.is_some() {
+            MIN_INTERVAL_MINUTES
+        } else {
+            self.interval_minutes.max(MIN_INTERVAL_MINUTES)
+        };
+        now.saturating_sub(last) >= minutes * 60_000
+    }
+
+    /// 记录刷新结果，`Ok(None)` 表示内容未变化
+    pub fn record(&mut self, result: Result<Option<usize>, String>, now: u64) {
+        self.last_checked = Some(now);
+        match result {
+            Ok(entry_count) => {
+                self.last_success = Some(now);
+                self.last_error = None;
+                if let Some(entry_count) = entry_count {
+                    self.entry_count = entry_count;
+                }
+            }
+            Err(e) => self.last_error = Some(e),
+        }
+    }
+
+    /// 缓存文件名（由订阅名称派生，避免名称中的特殊字符）
+    pub fn cache_file_name(&self) -> String {
+        format!("{:016x}.hosts", crate::storage::stable_hash(self.name.as_bytes()))
+    }
+
+    /// 按订阅的格式把下载的原始内容转换为 Hosts 格式
+    pub fn hosts_content(&self, raw: &str) -> String {
+        let sink_ip = convert::sink_ip(self.sink_ip.as_deref())
+            .unwrap_or_else(|_| convert::DEFAULT_SINK_IP.to_string());
+        convert::to_hosts(raw, self.format, &sink_ip)
+    }
+
+    /// 受管区块中对应的一段
+    pub fn section(&self, content: &str) -> ManagedSection {
+        ManagedSection {
+            title: format!("subscription: {}", self.name),
+            content: content.to_string(),
+        }
+    }
+}
+
+/// 统计内容中启用的条目数
+pub fn count_entries(content: &str) -> usize {
+    HostsDocument::parse(content)
+        .entries()
+        .iter()
+        .filter(|e| e.enabled)
+        .count()
+}
+
+/// 下载订阅内容（带条件请求头）
+pub async fn fetch(client: &reqwest::Client, sub: &HostsSubscription) -> Result<FetchOutcome, String> {
+    let mut request = client.get(&sub.url);
+    if let Some(etag) = &sub.etag {
+        request = request.header(IF_NONE_MATCH, etag);
+    }
+    if let Some(last_modified) = &sub.last_modified {
+        request = request.header(IF_MODIFIED_SINCE, last_modified);
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("请求失败: {}", e))?;
+
+    if response.status() == StatusCode::NOT_MODIFIED {
+        return Ok(FetchOutcome::NotModified);
+    }
+    if !response.status().is_success() {
+        return Err(format!("HTTP错误: {}", response.status()));
+    }
+
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string())
+    };
+    let etag = header(ETAG);
+    let last_modified = header(LAST_MODIFIED);
+    let content = super::validate::read_response(response).await?;
+
+    Ok(FetchOutcome::Updated {
+        content,
+        etag,
+        last_modified,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_due_and_record() {
+        let hour = 60 * 60_000;
+        let mut sub = HostsSubscription {
+            name: "github".to_string(),
+            url: "https://example.com/hosts".to_string(),
+            enabled: true,
+            interval_minutes: 120,
+            ..Default::default()
+        };
+        assert!(sub.is_due(0));
+
+        sub.record(Ok(Some(42)), 0);
+        assert_eq!((sub.entry_count, sub.last_success), (42, Some(0)));
+        assert!(!sub.is_due(hour));
+        assert!(sub.is_due(2 * hour));
+
+        // 失败时保留上次的条目数，并按最短间隔重试
+        sub.record(Err("timeout".to_string()), 2 * hour);
+        assert_eq!(sub.entry_count, 42);
+        assert_eq!(sub.last_success, Some(0));
+        assert!(sub.is_due(2 * hour + MIN_INTERVAL_MINUTES * 60_000));
+
+        sub.record(Ok(None), 3 * hour);
+        assert_eq!((sub.entry_count, sub.last_error.as_deref()), (42, None));
+
+        sub.enabled = false;
+        assert!(!sub.is_due(10 * hour));
+    }
+
+    #[test]
+    fn test_cache_file_name_is_stable() {
+        let sub = HostsSubscription {
+            name: "remote".to_string(),
+            ..Default::default()
+        };
+        // 文件名保存在磁盘上，升级后必须保持不变
+        assert_eq!(sub.cache_file_name(), "fff229536a18b7d3.hosts");
+    }
+
+    #[test]
+    fn test_count_entries_ignores_disabled_lines() {
+        assert_eq!(count_entries("# header\n1.1.1.1 a b\n# 2.2.2.2 c\n3.3.3.3 d\n"), 2);
+    }
+}
@@ -0,0 +1,316 @@
+//! 远程 Hosts 内容的校验与清理
+//!
+//! 下载的内容在写入系统 Hosts 之前需要经过：大小限制、内容类型检查（拒绝 HTML
+//! 错误页与二进制文件）、逐行语法检查，以及受保护域名检查（不允许覆盖 localhost、
+//! 公司内部域名与 Windows Update 相关域名）。被拒绝的行会记录在报告中。
+
+use super::parser::{format_entry, HostsDocument, LineKind};
+use reqwest::header::CONTENT_TYPE;
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+
+/// 远程内容大小上限：8 MiB
+pub const MAX_REMOTE_SIZE: usize = 8 * 1024 * 1024;
+
+/// 报告中最多保留的被拒绝行数
+const MAX_REPORTED_LINES: usize = 200;
+
+/// 只允许指向回环地址（或 `0.0.0.0` 等黑洞地址）的名称
+const LOOPBACK_NAMES: &[&str] = &[
+    "localhost",
+    "localhost.localdomain",
+    "ip6-localhost",
+    "ip6-loopback",
+];
+
+/// macOS 默认 Hosts 中的 `255.255.255.255 broadcasthost`
+const BROADCAST_NAME: &str = "broadcasthost";
+
+/// Windows Update 相关域名（包括其子域名），任何映射都会被拒绝
+const WINDOWS_UPDATE_DOMAINS: &[&str] = &[
+    "windowsupdate.com",
+    "windowsupdate.microsoft.com",
+    "update.microsoft.com",
+    "delivery.mp.microsoft.com",
+    "wustat.windows.com",
+    "ntservicepack.microsoft.com",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RejectedLine {
+    /// 行号，从 1 开始
+    pub line: usize,
+    pub text: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationReport {
+    pub accepted_count: usize,
+    pub rejected_count: usize,
+    /// 被拒绝的行（最多保留前 200 行）
+    pub rejected: Vec<RejectedLine>,
+}
+
+/// 清理后的内容与校验报告
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidatedHosts {
+    pub content: String,
+    pub report: ValidationReport,
+}
+
+impl ValidationReport {
+    fn reject(&mut self, line: usize, text: &str, reason: String) {
+        self.rejected_count += 1;
+        if self.rejected.len() < MAX_REPORTED_LINES {
+            self.rejected.push(RejectedLine {
+                line: line + 1,
+                text: text.to_string(),
+                reason,
+            });
+        }
+    }
+}
+
+/// 读取远程响应：先检查响应头，再限制大小地读取内容并检查内容本身
+pub async fn read_response(mut response: reqwest::Response) -> Result<String, String> {
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.to_string());
+    check_response_headers(content_type.as_deref(), response.content_length())?;
+
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("读取响应内容失败: {}", e))?
+    {
+        bytes.extend_from_slice(&chunk);
+        if bytes.len() > MAX_REMOTE_SIZE {
+            return Err(format!("远程内容过大（上限 {} 字节）", MAX_REMOTE_SIZE));
+        }
+    }
+    sniff_content(&bytes)
+}
+
+/// 在下载完成前根据响应头做检查
+fn check_response_headers(content_type: Option<&str>, content_length: Option<u64>) -> Result<(), String> {
+    if let Some(length) = content_length {
+        if length > MAX_REMOTE_SIZE as u64 {
+            return Err(format!("远程内容过大: {} 字节（上限 {} 字节）", length, MAX_REMOTE_SIZE));
+        }
+    }
+    if let Some(content_type) = content_type {
+        let mime = content_type.split(';').next().unwrap_or("").trim().to_lowercase();
+        if mime == "text/html" || mime == "application/xhtml+xml" {
+            return Err("远程返回的是网页而不是 Hosts 文件".to_string());
+        }
+        if mime.starts_with("image/") || mime.starts_with("audio/") || mime.starts_with("video/") {
+            return Err(format!("远程内容类型不是文本: {}", mime));
+        }
+    }
+    Ok(())
+}
+
+/// 根据内容本身判断是否像 Hosts 文本（拒绝 HTML 与二进制内容）
+fn sniff_content(bytes: &[u8]) -> Result<String, String> {
+    if bytes.len() > MAX_REMOTE_SIZE {
+        return Err(format!("远程内容过大: {} 字节（上限 {} 字节）", bytes.len(), MAX_REMOTE_SIZE));
+    }
+    if bytes.contains(&0) {
+        return Err("远程内容是二进制文件".to_string());
+    }
+    let text = String::from_utf8(bytes.to_vec()).map_err(|_| "远程内容不是 UTF-8 文本".to_string())?;
+    let text = text.trim_start_matches('\u{feff}').to_string();
+
+    let head = text.trim_start().chars().take(256).collect::<String>().to_lowercase();
+    if head.starts_with("<!doctype") || head.starts_with("<html") || head.starts_with("<?xml") {
+        return Err("远程返回的是网页而不是 Hosts 文件".to_string());
+    }
+    if text.trim().is_empty() {
+        return Err("远程内容为空".to_string());
+    }
+    Ok(text)
+}
+
+/// 逐行检查并清理内容：
+/// - 注释、空行与禁用的条目原样保留；
+/// - 语法错误的行整行丢弃；
+/// - 受保护的主机名从条目中移除，条目没有剩余主机名时整行丢弃。
+pub fn sanitize(content: &str, protected_domains: &[String]) -> ValidatedHosts {
+    let newline = if content.contains("\r\n") { "\r\n" } else { "\n" };
+    let doc = HostsDocument::parse(content);
+    let mut report = ValidationReport::default();
+    let mut lines = Vec::new();
+
+    for (index, line) in doc.lines.iter().enumerate() {
+        let text = line.text();
+        let data = match &line.kind {
+            LineKind::Blank | LineKind::Comment => {
+                lines.push(text.to_string());
+                continue;
+            }
+            LineKind::Other => {
+                report.reject(index, text, "无法识别的行".to_string());
+                continue;
+            }
+            LineKind::Entry(data) if !data.enabled => {
+                lines.push(text.to_string());
+                continue;
+            }
+            LineKind::Entry(data) => data,
+        };
+
+        let Ok(ip) = data.ip.parse::<IpAddr>() else {
+            report.reject(index, text, format!("无效的 IP 地址: {}", data.ip));
+            continue;
+        };
+        if let Some(bad) = data.hostnames.iter().find(|h| !is_valid_hostname(h)) {
+            report.reject(index, text, format!("无效的主机名: {}", bad));
+            continue;
+        }
+
+        let mut reasons = Vec::new();
+        let hostnames: Vec<String> = data
+            .hostnames
+            .iter()
+            .filter(|h| match protected_reason(h, ip, protected_domains) {
+                Some(reason) => {
+                    reasons.push(reason);
+                    false
+                }
+                None => true,
+            })
+            .cloned()
+            .collect();
+
+        if !reasons.is_empty() {
+            report.reject(index, text, reasons.join("; "));
+        }
+        if hostnames.is_empty() {
+            continue;
+        }
+        if hostnames.len() == data.hostnames.len() {
+            lines.push(text.to_string());
+        } else {
+            let mut data = data.clone();
+            data.hostnames = hostnames;
+            lines.push(format_entry(&data));
+        }
+        report.accepted_count += 1;
+    }
+
+    let mut content = lines.join(newline);
+    if !content.is_empty() {
+        content.push_str(newline);
+    }
+    ValidatedHosts { content, report }
+}
+
+/// 主机名语法检查：字母、数字、`-`、`_`，以 `.` 分隔，总长不超过 253，每段不超过 63
+fn is_valid_hostname(name: &str) -> bool {
+    let name = name.strip_suffix('.').unwrap_or(name);
+    !name.is_empty()
+        && name.len() <= 253
+        && name.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        })
+}
+
+/// 主机名受保护时返回拒绝原因
+fn protected_reason(name: &str, ip: IpAddr, protected_domains: &[String]) -> Option<String> {
+    let name = name.trim_end_matches('.').to_lowercase();
+
+    if LOOPBACK_NAMES.contains(&name.as_str()) {
+        // 0.0.0.0 / :: 同样不会把名称指向其他主机
+        return (!ip.is_loopback() && !ip.is_unspecified()).then(|| format!("{} 只能指向回环地址", name));
+    }
+    if name == BROADCAST_NAME {
+        let allowed = ip.is_loopback() || matches!(ip, IpAddr::V4(v4) if v4.is_broadcast());
+        return (!allowed).then(|| format!("{} 只能指向广播地址或回环地址", name));
+    }
+    if WINDOWS_UPDATE_DOMAINS.iter().any(|d| matches_domain(&name, d)) {
+        return Some(format!("{} 属于 Windows Update 域名", name));
+    }
+    protected_domains
+        .iter()
+        .map(|d| d.trim().trim_start_matches("*.").trim_end_matches('.').to_lowercase())
+        .find(|d| !d.is_empty() && matches_domain(&name, d))
+        .map(|d| format!("{} 属于受保护的域名 {}", name, d))
+}
+
+/// `name` 等于 `domain` 或是其子域名
+fn matches_domain(name: &str, domain: &str) -> bool {
+    name == domain
+        || name
+            .strip_suffix(domain)
+            .is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_rejects_html_and_binary() {
+        assert!(sniff_content(b"<!DOCTYPE html><html><body>404</body></html>").is_err());
+        assert!(sniff_content(b"  <html>").is_err());
+        assert!(sniff_content(b"1.1.1.1 a\0").is_err());
+        assert!(sniff_content(b"\n\n").is_err());
+        assert_eq!(sniff_content("\u{feff}1.1.1.1 a\n".as_bytes()).unwrap(), "1.1.1.1 a\n");
+
+        assert!(check_response_headers(Some("text/html; charset=utf-8"), None).is_err());
+        assert!(check_response_headers(Some("text/plain"), Some(MAX_REMOTE_SIZE as u64 + 1)).is_err());
+        assert!(check_response_headers(Some("text/plain"), Some(100)).is_ok());
+    }
+
+    #[test]
+    fn test_sanitize_reports_rejected_lines() {
+        let content = "# remote\n\
+                       127.0.0.1 localhost\n\
+                       1.2.3.4 localhost github.com\n\
+                       0.0.0.0 download.windowsupdate.com\n\
+                       10.1.1.1 intranet.corp.example other.example\n\
+                       999.1.1.1 bad-ip.example\n\
+                       1.1.1.1 bad_host!\n\
+                       just-a-word\n\
+                       # 2.2.2.2 disabled.example\n\
+                       140.82.112.4 github.com gist.github.com\n";
+        let result = sanitize(content, &["corp.example".to_string()]);
+
+        assert_eq!(
+            result.content,
+            "# remote\n\
+             127.0.0.1 localhost\n\
+             1.2.3.4\tgithub.com\n\
+             10.1.1.1\tother.example\n\
+             # 2.2.2.2 disabled.example\n\
+             140.82.112.4 github.com gist.github.com\n"
+        );
+        let lines: Vec<usize> = result.report.rejected.iter().map(|r| r.line).collect();
+        assert_eq!(lines, vec![3, 4, 5, 6, 7, 8]);
+        assert_eq!(result.report.accepted_count, 4);
+        assert_eq!(result.report.rejected_count, 6);
+        assert!(result.report.rejected[2].reason.contains("corp.example"));
+    }
+
+    #[test]
+    fn test_loopback_and_broadcast_names() {
+        let content = "255.255.255.255 broadcasthost\n\
+                       0.0.0.0 localhost\n\
+                       :: ip6-localhost\n\
+                       ::1 ip6-loopback\n\
+                       10.0.0.1 broadcasthost\n\
+                       255.255.255.255 localhost\n";
+        let result = sanitize(content, &[]);
+        assert_eq!(result.report.accepted_count, 4);
+        let lines: Vec<usize> = result.report.rejected.iter().map(|r| r.line).collect();
+        assert_eq!(lines, vec![5, 6]);
+    }
+}
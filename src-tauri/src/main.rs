@@ -2,18 +2,20 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod network;
+mod dns;
 mod hosts;
 mod proxy;
 mod scenes;
 mod tray_icon;
 mod admin;
+mod storage;
 
 use hosts::*;
 use proxy::*;
 use scenes::*;
 use tray_icon::{TrayIconGenerator, update_tray_icon_color};
 use admin::{check_admin_privileges, request_admin_privileges, is_elevated_check};
-use tauri::{Manager, menu::{Menu, MenuItem}, tray::{TrayIconBuilder, TrayIconEvent}};
+use tauri::{Listener, Manager, menu::{CheckMenuItem, IsMenuItem, Menu, MenuItem, Submenu}, tray::{TrayIconBuilder, TrayIconEvent}};
 use std::fs;
 use std::path::PathBuf;
 
@@ -74,15 +76,7 @@ fn main() {
     tauri::Builder::default()
         .setup(|app| {
             // 创建系统托盘菜单（统一使用英文，避免中英文混杂）
-            let show_item = MenuItem::with_id(app, "show", "Show window", true, None::<&str>)?;
-            let hide_item = MenuItem::with_id(app, "hide", "Hide window", true, None::<&str>)?;
-            let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-            
-            let menu = Menu::with_items(app, &[
-                &show_item,
-                &hide_item,
-                &quit_item,
-            ])?;
+            let menu = build_tray_menu(app.handle())?;
 
             // 生成自定义托盘图标（方形，包含 "IP" 文字）
             // 使用微信大小的图标（32x32 像素）
@@ -146,10 +140,46 @@ fn main() {
                     "quit" => {
                         std::process::exit(0);
                     }
-                    _ => {}
+                    id => {
+                        // 托盘中切换代理方案
+                        if let Some(name) = id.strip_prefix(PROXY_PROFILE_MENU_PREFIX) {
+                            let app = app_handle.clone();
+                            let name = name.to_string();
+                            tauri::async_runtime::spawn(async move {
+                                if let Err(e) = apply_proxy_profile(app, name).await {
+                                    eprintln!("应用代理方案失败: {}", e);
+                                }
+                            });
+                        }
+                    }
                 }
             });
 
+            // 代理方案或系统代理变化时重建托盘菜单，更新勾选状态
+            for event in [PROFILES_CHANGED_EVENT, PROXY_CHANGED_EVENT] {
+                let app_handle = app.handle().clone();
+                app.listen_any(event, move |_| refresh_tray_menu(&app_handle));
+            }
+
+            // 监听场景目录，外部修改场景文件时通知前端
+            if let Err(e) = start_scene_watcher(app.handle()) {
+                eprintln!("{}", e);
+            }
+
+            // 恢复手动指定的 Hosts 路径（需在订阅刷新之前）
+            if let Err(e) = init_hosts_path(app.handle()) {
+                eprintln!("{}", e);
+            }
+
+            // 后台定时刷新 Hosts 订阅
+            start_hosts_subscription_scheduler(app.handle().clone());
+
+            // 恢复本机 PAC 服务
+            init_pac_server(app.handle());
+
+            // 监听其他程序对系统代理的修改
+            init_proxy_watcher(app.handle());
+
             // 监听窗口关闭事件，点击关闭按钮时隐藏到托盘而不是退出
             if let Some(window) = app.get_webview_window("main") {
                 let window_clone = window.clone();
@@ -173,11 +203,52 @@ fn main() {
             network::enable_adapter,
             network::get_adapter_media_states,
             set_dns_servers,
+            dns::flush_dns_cache,
+            dns::get_dns_cache_entries,
             get_hosts,
             set_hosts,
             fetch_remote_hosts,
+            get_hosts_entries,
+            add_hosts_entry,
+            remove_hosts_entry,
+            toggle_hosts_entry,
+            update_hosts_entry,
+            get_hosts_path_info,
+            set_hosts_path,
+            lint_hosts,
+            normalize_hosts,
+            list_hosts_backups,
+            diff_hosts_backup,
+            restore_hosts_backup,
+            list_hosts_groups,
+            save_hosts_group,
+            delete_hosts_group,
+            set_hosts_group_enabled,
+            list_hosts_subscriptions,
+            save_hosts_subscription,
+            delete_hosts_subscription,
+            refresh_hosts_subscriptions,
+            get_hosts_protected_domains,
+            set_hosts_protected_domains,
+            get_hosts_allowlist,
+            set_hosts_allowlist,
+            get_hosts_allowlist_suppressed,
             get_proxy,
             set_proxy,
+            get_pac_settings,
+            save_pac_settings,
+            preview_pac_script,
+            evaluate_pac,
+            test_proxy,
+            get_proxy_propagation,
+            set_proxy_propagation,
+            check_proxy_bypass,
+            get_proxy_bypass_presets,
+            list_proxy_profiles,
+            save_proxy_profile,
+            delete_proxy_profile,
+            apply_proxy_profile,
+            get_proxy_profile_username,
             get_scenes,
             save_scene,
             apply_scene,
@@ -187,6 +258,19 @@ fn main() {
             import_scenes,
             export_scenes_json,
             import_scenes_json,
+            export_scene_linux,
+            export_scenes_bundle,
+            get_publisher_key,
+            list_trusted_publishers,
+            add_trusted_publisher,
+            remove_trusted_publisher,
+            list_scene_sources,
+            add_scene_source,
+            remove_scene_source,
+            refresh_scene_sources,
+            fork_scene,
+            search_scenes,
+            import_scene_linux,
             update_tray_icon_color,
             save_backup,
             restore_backup,
@@ -203,6 +287,51 @@ fn main() {
         .expect("error while running tauri application");
 }
 
+/// 托盘菜单中代理方案项的 id 前缀
+const PROXY_PROFILE_MENU_PREFIX: &str = "proxy-profile:";
+
+/// 构建托盘菜单（代理方案子菜单中勾选与系统当前设置一致的方案）
+fn build_tray_menu(app: &tauri::AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
+    let profile_items = proxy_profile_menu_items(app)
+        .into_iter()
+        .map(|(name, active)| {
+            let label = if name == DIRECT_PROFILE { "Direct".to_string() } else { name.clone() };
+            let id = format!("{}{}", PROXY_PROFILE_MENU_PREFIX, name);
+            CheckMenuItem::with_id(app, id, label, true, active, None::<&str>)
+        })
+        .collect::<tauri::Result<Vec<_>>>()?;
+    let profile_refs: Vec<&dyn IsMenuItem<tauri::Wry>> =
+        profile_items.iter().map(|item| item as &dyn IsMenuItem<tauri::Wry>).collect();
+    let proxy_menu = Submenu::with_items(app, "Proxy", true, &profile_refs)?;
+
+    let show_item = MenuItem::with_id(app, "show", "Show window", true, None::<&str>)?;
+    let hide_item = MenuItem::with_id(app, "hide", "Hide window", true, None::<&str>)?;
+    let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+
+    Menu::with_items(app, &[
+        &proxy_menu,
+        &show_item,
+        &hide_item,
+        &quit_item,
+    ])
+}
+
+/// 重建托盘菜单
+fn refresh_tray_menu(app: &tauri::AppHandle) {
+    let menu = match build_tray_menu(app) {
+        Ok(menu) => menu,
+        Err(e) => {
+            eprintln!("重建托盘菜单失败: {}", e);
+            return;
+        }
+    };
+    if let Some(tray_handle) = app.try_state::<std::sync::Mutex<tauri::tray::TrayIcon>>() {
+        if let Ok(tray) = tray_handle.lock() {
+            let _ = tray.set_menu(Some(menu));
+        }
+    }
+}
+
 /// 单独设置 DNS 服务器（不改变 IP 获取方式，可用于 DHCP + 自定义 DNS）
 ///
 /// NOTE:
@@ -14,7 +14,7 @@ fn get_network_info_mutex() -> &'static tokio::sync::Mutex<()> {
 }
 
 /// 创建 PowerShell 命令（Windows 下禁止弹出控制台窗口）
-fn powershell_cmd() -> Command {
+pub(crate) fn powershell_cmd() -> Command {
     let mut cmd = Command::new("powershell");
 
     // 统一加上更“安静”的 PowerShell 参数，减少环境干扰
@@ -485,7 +485,7 @@ fn get_ip_config(adapter_name: &str) -> Result<IpConfig, String> {
     })
 }
 
-fn prefix_to_subnet(prefix: u8) -> Option<String> {
+pub(crate) fn prefix_to_subnet(prefix: u8) -> Option<String> {
     if prefix > 32 {
         return None;
     }
@@ -651,6 +651,7 @@ pub async fn set_static_ip(
         check_powershell_output(&set_dns_output, &format!("为网卡 {} 设置DNS", adapter_name))?;
     }
 
+    crate::dns::flush_after_change();
     Ok(())
 }
 
@@ -714,6 +715,8 @@ pub async fn set_dhcp(adapter_name: String) -> Result<(), String> {
         ])
         .output();
 
+    // DHCP 下发的 DNS 可能与之前不同
+    crate::dns::flush_after_change();
     Ok(())
 }
 
@@ -737,6 +740,7 @@ pub async fn set_dns_servers_internal(adapter_name: String, dns: Vec<String>) ->
         .map_err(|e| format!("执行设置DNS命令失败: {}", e))?;
 
     check_powershell_output(&set_dns_output, &format!("为网卡 {} 设置DNS", adapter_name))?;
+    crate::dns::flush_after_change();
     Ok(())
 }
 
@@ -1090,7 +1094,7 @@ pub struct StaticConfig {
     pub dns: Option<Vec<String>>,
 }
 
-fn subnet_to_prefix(subnet: &str) -> Result<u8, String> {
+pub(crate) fn subnet_to_prefix(subnet: &str) -> Result<u8, String> {
     let parts: Vec<&str> = subnet.split('.').collect();
     if parts.len() != 4 {
         return Err("无效的子网掩码格式".to_string());
@@ -2,21 +2,102 @@ use serde::{Deserialize, Serialize};
 use winreg::enums::*;
 use winreg::RegKey;
 
-#[derive(Debug, Serialize, Deserialize)]
+mod bypass;
+mod connection;
+mod credentials;
+mod pac;
+mod pac_eval;
+mod pac_server;
+mod probe;
+mod profiles;
+mod propagate;
+mod servers;
+mod watcher;
+
+use crate::storage;
+use tauri::Emitter;
+pub use bypass::{BypassCheck, BypassPreset};
+pub use credentials::ProxyCredential;
+pub use pac::{PacMode, PacRule, PacSettings};
+pub use pac_eval::PacEvaluation;
+pub use probe::ProxyTestResult;
+pub use profiles::{ProxyProfile, DIRECT_PROFILE};
+pub use propagate::{PropagationResult, PropagationSettings};
+use connection::ConnectionSettings;
+use credentials::{CredentialStore, KeyringStore};
+use propagate::{PropagationState, ProxyEndpoints};
+pub use servers::ProxyServers;
+pub use watcher::EVENT as PROXY_CHANGED_EVENT;
+
+const INTERNET_SETTINGS_KEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Internet Settings";
+const CONNECTIONS_KEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Internet Settings\\Connections";
+const PAC_SETTINGS_FILE: &str = "pac_settings.json";
+const PROPAGATION_FILE: &str = "proxy_propagation.json";
+const PROFILES_FILE: &str = "proxy_profiles.json";
+
+/// 代理方案列表变化时发送（托盘菜单据此重建）
+pub const PROFILES_CHANGED_EVENT: &str = "proxy-profiles-changed";
+
+fn get_propagation_mutex() -> &'static tokio::sync::Mutex<()> {
+    static INSTANCE: std::sync::OnceLock<tokio::sync::Mutex<()>> = std::sync::OnceLock::new();
+    INSTANCE.get_or_init(|| tokio::sync::Mutex::new(()))
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ProxyConfig {
     pub enabled: bool,
-    pub server: String,
+    pub servers: ProxyServers,
     pub bypass: Vec<String>,
     pub auto_detect: bool,
     pub auto_config_url: String,
 }
 
-#[tauri::command]
-pub async fn get_proxy() -> Result<ProxyConfig, String> {
+/// 读取 `DefaultConnectionSettings`，不存在或无法解析时返回 `None`
+fn read_connection_settings() -> Option<ConnectionSettings> {
+    let value = RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey(CONNECTIONS_KEY)
+        .and_then(|key| key.get_raw_value(connection::VALUE_NAME))
+        .ok()?;
+    match ConnectionSettings::parse(&value.bytes) {
+        Ok(settings) => Some(settings),
+        Err(e) => {
+            eprintln!("解析 {} 失败: {}", connection::VALUE_NAME, e);
+            None
+        }
+    }
+}
+
+/// 修改并写回 `DefaultConnectionSettings`（不存在时新建）
+fn update_connection_settings(update: impl FnOnce(&mut ConnectionSettings)) -> Result<(), String> {
+    let mut settings = read_connection_settings().unwrap_or_default();
+    update(&mut settings);
+    settings.bump_counter();
+
+    let (connections, _) = RegKey::predef(HKEY_CURRENT_USER)
+        .create_subkey(CONNECTIONS_KEY)
+        .map_err(|e| format!("打开注册表失败: {}", e))?;
+    connections
+        .set_raw_value(
+            connection::VALUE_NAME,
+            &winreg::RegValue {
+                bytes: settings.to_bytes(),
+                vtype: REG_BINARY,
+            },
+        )
+        .map_err(|e| format!("设置连接代理设置失败: {}", e))
+}
+
+/// 读取系统当前的代理设置。
+/// 存在 `DefaultConnectionSettings` 时以它为准（系统实际使用的值），否则读取旧版的单项值
+fn read_proxy_config() -> Result<ProxyConfig, String> {
+    if let Some(settings) = read_connection_settings() {
+        return Ok(settings.to_config());
+    }
+
     let hkcu = RegKey::predef(HKEY_CURRENT_USER);
     let internet_settings = hkcu
-        .open_subkey("Software\\Microsoft\\Windows\\CurrentVersion\\Internet Settings")
+        .open_subkey(INTERNET_SETTINGS_KEY)
         .map_err(|e| format!("打开注册表失败: {}", e))?;
 
     let proxy_enable: u32 = internet_settings
@@ -41,75 +122,124 @@ pub async fn get_proxy() -> Result<ProxyConfig, String> {
         .get_value("AutoConfigURL")
         .unwrap_or_else(|_| "".to_string());
 
-    let bypass = if proxy_override.is_empty() {
-        Vec::new()
-    } else {
-        proxy_override.split(';').map(|s| s.trim().to_string()).collect()
-    };
-
     Ok(ProxyConfig {
         enabled: proxy_enable != 0,
-        server: proxy_server,
-        bypass,
+        servers: ProxyServers::parse(&proxy_server),
+        bypass: bypass::parse_registry(&proxy_override),
         auto_detect: auto_detect != 0,
         auto_config_url,
     })
 }
 
-#[tauri::command]
-pub async fn set_proxy(
-    enabled: bool,
-    server: String,
-    bypass: Vec<String>,
-    autoDetect: bool,
-    autoConfigUrl: String,
-) -> Result<(), String> {
+/// 校验并规范化为写入注册表后读回的形式（CIDR 已展开）
+fn normalize_config(config: &ProxyConfig) -> Result<ProxyConfig, String> {
+    let servers = config.servers.normalized()?;
+    if config.enabled && servers.is_empty() {
+        return Err("启用代理时必须填写代理服务器地址".to_string());
+    }
+    Ok(ProxyConfig {
+        servers,
+        bypass: bypass::to_registry_list(&config.bypass)?,
+        auto_config_url: config.auto_config_url.trim().to_string(),
+        ..config.clone()
+    })
+}
+
+/// 写入代理设置并通知系统
+fn write_proxy_config(config: &ProxyConfig) -> Result<(), String> {
+    let config = &normalize_config(config)?;
+
     let hkcu = RegKey::predef(HKEY_CURRENT_USER);
     let (internet_settings, _) = hkcu
-        .create_subkey("Software\\Microsoft\\Windows\\CurrentVersion\\Internet Settings")
+        .create_subkey(INTERNET_SETTINGS_KEY)
         .map_err(|e| format!("打开注册表失败: {}", e))?;
 
     internet_settings
-        .set_value("ProxyEnable", &(if enabled { 1u32 } else { 0u32 }))
+        .set_value("ProxyEnable", &(if config.enabled { 1u32 } else { 0u32 }))
         .map_err(|e| format!("设置代理状态失败: {}", e))?;
 
-    if enabled {
+    if config.enabled {
         internet_settings
-            .set_value("ProxyServer", &server)
+            .set_value("ProxyServer", &config.servers.to_registry_string())
             .map_err(|e| format!("设置代理服务器失败: {}", e))?;
 
-        let bypass_str = if bypass.is_empty() {
-            "".to_string()
-        } else {
-            bypass.join(";")
-        };
-
         internet_settings
-            .set_value("ProxyOverride", &bypass_str)
+            .set_value("ProxyOverride", &config.bypass.join(";"))
             .map_err(|e| format!("设置代理绕过列表失败: {}", e))?;
     }
 
     // 自动检测开关
     internet_settings
-        .set_value("AutoDetect", &(if autoDetect { 1u32 } else { 0u32 }))
+        .set_value("AutoDetect", &(if config.auto_detect { 1u32 } else { 0u32 }))
         .map_err(|e| format!("设置自动检测失败: {}", e))?;
 
     // 自动配置脚本地址（PAC）
-    if autoConfigUrl.trim().is_empty() {
+    if config.auto_config_url.trim().is_empty() {
         // 清空时删除键值，避免残留
         let _ = internet_settings.delete_value("AutoConfigURL");
     } else {
         internet_settings
-            .set_value("AutoConfigURL", &autoConfigUrl)
+            .set_value("AutoConfigURL", &config.auto_config_url)
             .map_err(|e| format!("设置自动配置脚本地址失败: {}", e))?;
     }
 
-    // 通知系统代理设置已更改
+    // 同步写入系统实际使用的二进制设置
+    update_connection_settings(|settings| settings.apply(config))?;
+
+    notify_settings_changed();
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_proxy() -> Result<ProxyConfig, String> {
+    read_proxy_config()
+}
+
+#[tauri::command]
+pub async fn set_proxy(
+    app: tauri::AppHandle,
+    enabled: bool,
+    servers: ProxyServers,
+    bypass: Vec<String>,
+    autoDetect: bool,
+    autoConfigUrl: String,
+) -> Result<(), String> {
+    apply_proxy_config(
+        &app,
+        &ProxyConfig {
+            enabled,
+            servers,
+            bypass,
+            auto_detect: autoDetect,
+            auto_config_url: autoConfigUrl,
+        },
+    )
+    .await
+}
+
+/// 写入系统代理并同步到环境变量与开发工具（同步失败不影响系统代理本身）
+async fn apply_proxy_config(app: &tauri::AppHandle, config: &ProxyConfig) -> Result<(), String> {
+    write_proxy_config(config)?;
+    for result in sync_propagation(app).await? {
+        if let Some(error) = result.error {
+            eprintln!("同步代理到 {:?} 失败: {}", result.target, error);
+        }
+    }
+    Ok(())
+}
+
+/// 通知系统代理设置已更改，并记录写入后的设置供监听器识别
+fn notify_settings_changed() {
+    if let Ok(config) = read_proxy_config() {
+        watcher::record_own_write(config);
+    }
+
     unsafe {
         use winapi::um::wininet::InternetSetOptionW;
         use winapi::um::wininet::INTERNET_OPTION_SETTINGS_CHANGED;
         use winapi::um::wininet::INTERNET_OPTION_REFRESH;
-        
+
         InternetSetOptionW(
             std::ptr::null_mut(),
             INTERNET_OPTION_SETTINGS_CHANGED,
@@ -123,6 +253,399 @@ pub async fn set_proxy(
             0,
         );
     }
+}
 
+fn read_auto_config_url() -> String {
+    if let Some(settings) = read_connection_settings() {
+        return settings.to_config().auto_config_url;
+    }
+    RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey(INTERNET_SETTINGS_KEY)
+        .and_then(|key| key.get_value::<String, _>("AutoConfigURL"))
+        .unwrap_or_default()
+}
+
+/// 设置（`None` 时删除）系统的自动配置脚本地址
+fn write_auto_config_url(url: Option<&str>) -> Result<(), String> {
+    let (internet_settings, _) = RegKey::predef(HKEY_CURRENT_USER)
+        .create_subkey(INTERNET_SETTINGS_KEY)
+        .map_err(|e| format!("打开注册表失败: {}", e))?;
+    match url {
+        Some(url) => internet_settings
+            .set_value("AutoConfigURL", &url)
+            .map_err(|e| format!("设置自动配置脚本地址失败: {}", e))?,
+        None => {
+            let _ = internet_settings.delete_value("AutoConfigURL");
+        }
+    }
+    update_connection_settings(|settings| settings.set_auto_config_url(url))?;
+    notify_settings_changed();
     Ok(())
 }
+
+fn load_pac_settings(app: &tauri::AppHandle) -> Result<PacSettings, String> {
+    storage::load_json(&storage::app_data_file(app, PAC_SETTINGS_FILE)?)
+}
+
+/// 按设置启动（或停止）本机 PAC 服务并更新 `AutoConfigURL`，返回正在使用的地址。
+/// `previous_port` 为修改前的端口，停用时若系统仍指向旧地址也一并清除
+async fn apply_pac_settings(settings: &PacSettings, previous_port: u16) -> Result<Option<String>, String> {
+    if settings.enabled {
+        let script = settings.script()?;
+        let url = pac::script_url(settings.port, &script);
+        pac_server::start(settings.port, script).await?;
+        write_auto_config_url(Some(&url))?;
+        return Ok(Some(url));
+    }
+
+    pac_server::stop().await;
+    let current = read_auto_config_url();
+    let points_to_us = [settings.port, previous_port]
+        .iter()
+        .any(|port| current.starts_with(&pac::base_url(*port)));
+    if points_to_us {
+        write_auto_config_url(None)?;
+    }
+    Ok(None)
+}
+
+/// 应用启动时恢复 PAC 服务
+pub fn init_pac_server(app: &tauri::AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = match load_pac_settings(&app) {
+            Ok(settings) if settings.enabled => apply_pac_settings(&settings, settings.port).await.map(|_| ()),
+            Ok(_) => Ok(()),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            eprintln!("启动 PAC 服务失败: {}", e);
+        }
+    });
+}
+
+/// 监听其他程序对系统代理的修改，变化时发送 `proxy-changed` 事件
+pub fn init_proxy_watcher(app: &tauri::AppHandle) {
+    watcher::start(app.clone(), read_proxy_config);
+}
+
+/// 获取 PAC 设置
+#[tauri::command]
+pub async fn get_pac_settings(app: tauri::AppHandle) -> Result<PacSettings, String> {
+    load_pac_settings(&app)
+}
+
+/// 保存 PAC 设置并立即生效，返回系统 `AutoConfigURL` 的新值（停用时为空）
+#[tauri::command]
+pub async fn save_pac_settings(app: tauri::AppHandle, settings: PacSettings) -> Result<Option<String>, String> {
+    if settings.port == 0 {
+        return Err("PAC 服务端口无效".to_string());
+    }
+    // 先校验脚本，避免保存无法使用的设置
+    settings.script()?;
+    let previous = load_pac_settings(&app)?;
+    storage::save_json(&storage::app_data_file(&app, PAC_SETTINGS_FILE)?, &settings)?;
+    apply_pac_settings(&settings, previous.port).await
+}
+
+/// 预览按设置生成的 PAC 脚本
+#[tauri::command]
+pub async fn preview_pac_script(settings: PacSettings) -> Result<String, String> {
+    settings.script()
+}
+
+/// 读取 PAC 脚本：`http(s)://` 地址直接下载（不经过代理），其他按本地文件处理
+async fn load_pac_script(source: &str) -> Result<String, String> {
+    if source.starts_with("http://") || source.starts_with("https://") {
+        let client = reqwest::Client::builder()
+            .no_proxy()
+            .timeout(std::time::Duration::from_secs(15))
+            .build()
+            .map_err(|e| format!("创建HTTP客户端失败: {}", e))?;
+        let response = client
+            .get(source)
+            .send()
+            .await
+            .map_err(|e| format!("下载 PAC 脚本失败: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("下载 PAC 脚本失败: HTTP {}", response.status()));
+        }
+        return response.text().await.map_err(|e| format!("读取 PAC 脚本失败: {}", e));
+    }
+
+    let path = source
+        .strip_prefix("file:///")
+        .or_else(|| source.strip_prefix("file://"))
+        .unwrap_or(source);
+    std::fs::read_to_string(path).map_err(|e| format!("读取 PAC 脚本 {} 失败: {}", path, e))
+}
+
+/// 检查某个地址会使用哪个代理。
+/// `source` 为 PAC 地址或本地文件路径，未指定时使用系统当前的 `AutoConfigURL`
+#[tauri::command]
+pub async fn evaluate_pac(url: String, source: Option<String>) -> Result<PacEvaluation, String> {
+    let source = source
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(read_auto_config_url);
+    if source.is_empty() {
+        return Err("未配置自动配置脚本地址".to_string());
+    }
+
+    let script = load_pac_script(&source).await?;
+    let (url, host) = pac_eval::split_url(&url)?;
+    // 脚本中的 dnsResolve 会阻塞
+    tokio::task::spawn_blocking(move || {
+        pac_eval::evaluate(&script, &url, &host, &pac_eval::local_ip_address(), pac_eval::system_resolve)
+    })
+    .await
+    .map_err(|e| format!("spawn_blocking 失败: {}", e))?
+}
+
+/// 经由代理访问测试地址，检查代理是否可用（不修改系统设置）。
+/// `servers` 为待测试的代理，未指定时测试系统当前的代理
+#[tauri::command]
+pub async fn test_proxy(
+    servers: Option<ProxyServers>,
+    target_url: Option<String>,
+    egress_url: Option<String>,
+) -> Result<ProxyTestResult, String> {
+    let servers = match servers {
+        Some(servers) => servers.normalized()?,
+        None => get_proxy().await?.servers,
+    };
+    let non_empty = |value: Option<String>, default: &str| {
+        value
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .unwrap_or_else(|| default.to_string())
+    };
+    probe::run(
+        &servers,
+        &non_empty(target_url, probe::DEFAULT_TARGET_URL),
+        &non_empty(egress_url, probe::DEFAULT_EGRESS_URL),
+    )
+    .await
+}
+
+fn load_propagation(app: &tauri::AppHandle) -> Result<PropagationState, String> {
+    storage::load_json(&storage::app_data_file(app, PROPAGATION_FILE)?)
+}
+
+/// 按系统当前的代理同步环境变量与开发工具（可选修改启用的目标）
+async fn sync_propagation_with(
+    app: &tauri::AppHandle,
+    settings: Option<PropagationSettings>,
+) -> Result<Vec<PropagationResult>, String> {
+    let _guard = get_propagation_mutex().lock().await;
+    let mut state = load_propagation(app)?;
+    if let Some(settings) = settings {
+        state.set_settings(settings);
+    }
+    let config = read_proxy_config()?;
+    let endpoints = ProxyEndpoints::from_config(&config);
+    // 没有目标需要认证信息时不读取凭据库
+    let credential = if state.credential_targets.is_empty() {
+        None
+    } else {
+        active_credential(app, &config)
+    };
+
+    // git/npm 等命令可能较慢，放到阻塞线程中执行
+    let (state, results) = tokio::task::spawn_blocking(move || {
+        let results = propagate::sync(&mut state, endpoints.as_ref(), credential.as_ref());
+        (state, results)
+    })
+    .await
+    .map_err(|e| format!("spawn_blocking 失败: {}", e))?;
+
+    storage::save_json(&storage::app_data_file(app, PROPAGATION_FILE)?, &state)?;
+    Ok(results)
+}
+
+async fn sync_propagation(app: &tauri::AppHandle) -> Result<Vec<PropagationResult>, String> {
+    sync_propagation_with(app, None).await
+}
+
+/// 获取启用的代理同步目标
+#[tauri::command]
+pub async fn get_proxy_propagation(app: tauri::AppHandle) -> Result<PropagationSettings, String> {
+    Ok(load_propagation(&app)?.settings())
+}
+
+/// 设置代理同步目标并立即按当前代理同步，取消的目标恢复原设置
+#[tauri::command]
+pub async fn set_proxy_propagation(
+    app: tauri::AppHandle,
+    settings: PropagationSettings,
+) -> Result<Vec<PropagationResult>, String> {
+    sync_propagation_with(&app, Some(settings)).await
+}
+
+/// 逐项校验绕过列表，返回每项的类型与写入注册表的形式
+#[tauri::command]
+pub async fn check_proxy_bypass(entries: Vec<String>) -> Result<Vec<BypassCheck>, String> {
+    Ok(bypass::check(&entries))
+}
+
+/// 常用的绕过列表（私有地址段、公司内部域名后缀）
+#[tauri::command]
+pub async fn get_proxy_bypass_presets() -> Result<Vec<BypassPreset>, String> {
+    Ok(bypass::presets())
+}
+
+fn load_profiles(app: &tauri::AppHandle) -> Result<Vec<ProxyProfile>, String> {
+    storage::load_json(&storage::app_data_file(app, PROFILES_FILE)?)
+}
+
+fn edit_profiles(
+    app: &tauri::AppHandle,
+    edit: impl FnOnce(&mut Vec<ProxyProfile>) -> Result<(), String>,
+) -> Result<Vec<ProxyProfile>, String> {
+    let mut saved = load_profiles(app)?;
+    edit(&mut saved)?;
+    storage::save_json(&storage::app_data_file(app, PROFILES_FILE)?, &saved)?;
+    let _ = app.emit(PROFILES_CHANGED_EVENT, ());
+    Ok(profiles::with_builtin(saved))
+}
+
+/// 与当前系统设置一致、且保存了认证信息的方案的凭据
+fn active_credential(app: &tauri::AppHandle, config: &ProxyConfig) -> Option<ProxyCredential> {
+    let id = load_profiles(app)
+        .ok()?
+        .into_iter()
+        .filter(|profile| profile.is_active(config))
+        .find_map(|profile| profile.credential)?;
+    match KeyringStore.get(&id) {
+        Ok(credential) => credential,
+        Err(e) => {
+            eprintln!("{}", e);
+            None
+        }
+    }
+}
+
+/// 去掉代理地址中的 `user:pass@`（导出场景时使用）
+pub fn strip_proxy_credentials(server: &str) -> String {
+    match credentials::strip_servers(&ProxyServers::parse(server)) {
+        (servers, Some(_)) => servers.to_registry_string(),
+        (_, None) => server.to_string(),
+    }
+}
+
+/// 托盘菜单中的代理方案：`(名称, 是否与系统当前设置一致)`
+pub fn proxy_profile_menu_items(app: &tauri::AppHandle) -> Vec<(String, bool)> {
+    let current = read_proxy_config().ok();
+    profiles::with_builtin(load_profiles(app).unwrap_or_default())
+        .into_iter()
+        .map(|profile| {
+            let active = current.as_ref().is_some_and(|c| profile.is_active(c));
+            (profile.name, active)
+        })
+        .collect()
+}
+
+/// 获取代理方案（内置的 direct 在最前）
+#[tauri::command]
+pub async fn list_proxy_profiles(app: tauri::AppHandle) -> Result<Vec<ProxyProfile>, String> {
+    Ok(profiles::with_builtin(load_profiles(&app)?))
+}
+
+/// 方案记录的凭据 id
+fn profile_credential_id(app: &tauri::AppHandle, name: &str) -> Result<Option<String>, String> {
+    Ok(load_profiles(app)?
+        .into_iter()
+        .find(|p| p.name == name)
+        .and_then(|p| p.credential))
+}
+
+/// 新建或修改代理方案（按名称匹配）。
+/// `credential` 为空时保留原有的认证信息，用户名为空时删除；
+/// `source` 是编辑器中选中的方案，另存为其他名称时复制它的认证信息；
+/// 服务器地址中的 `user:pass@` 会移到系统凭据库中
+#[tauri::command]
+pub async fn save_proxy_profile(
+    app: tauri::AppHandle,
+    name: String,
+    config: ProxyConfig,
+    credential: Option<ProxyCredential>,
+    source: Option<String>,
+) -> Result<Vec<ProxyProfile>, String> {
+    let name = profiles::validate_name(&name)?;
+    let (servers, embedded) = credentials::strip_servers(&config.servers);
+    let config = normalize_config(&ProxyConfig { servers, ..config })?;
+    let previous = profile_credential_id(&app, &name)?;
+
+    let credential = match (credential.or(embedded), source.filter(|source| *source != name)) {
+        (None, Some(source)) => {
+            let copied = match profile_credential_id(&app, &source)? {
+                Some(id) => KeyringStore.get(&id)?,
+                None => None,
+            };
+            // 原方案没有认证信息时按清空处理，与编辑器中显示的一致
+            Some(copied.unwrap_or(ProxyCredential {
+                username: String::new(),
+                password: String::new(),
+            }))
+        }
+        (credential, _) => credential,
+    };
+    let credential = credentials::update(&KeyringStore, &name, previous, credential)?;
+
+    edit_profiles(&app, |saved| {
+        profiles::upsert(
+            saved,
+            ProxyProfile {
+                name,
+                builtin: false,
+                config,
+                credential,
+            },
+        );
+        Ok(())
+    })
+}
+
+/// 删除代理方案及其认证信息
+#[tauri::command]
+pub async fn delete_proxy_profile(app: tauri::AppHandle, name: String) -> Result<Vec<ProxyProfile>, String> {
+    let credential = profile_credential_id(&app, &name)?;
+    let profiles = edit_profiles(&app, |saved| profiles::remove(saved, &name))?;
+    if let Some(id) = credential {
+        KeyringStore.delete(&id)?;
+    }
+    Ok(profiles)
+}
+
+/// 代理方案保存的认证用户名（不返回密码）
+#[tauri::command]
+pub async fn get_proxy_profile_username(app: tauri::AppHandle, name: String) -> Result<Option<String>, String> {
+    let Some(id) = profile_credential_id(&app, &name)? else {
+        return Ok(None);
+    };
+    Ok(KeyringStore.get(&id)?.map(|c| c.username))
+}
+
+/// 应用代理方案（界面与托盘菜单共用）
+#[tauri::command]
+pub async fn apply_proxy_profile(app: tauri::AppHandle, name: String) -> Result<(), String> {
+    let profile = profiles::with_builtin(load_profiles(&app)?)
+        .into_iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| format!("代理方案 {} 不存在", name))?;
+    apply_proxy_config(&app, &profile.config).await?;
+    sync_pac_with_config(&app, &profile.config).await
+}
+
+/// 按方案的自动配置脚本地址开关本机 PAC 服务并保存，
+/// 否则下次启动时 `init_pac_server` 会把 `AutoConfigURL` 重新指向本机
+async fn sync_pac_with_config(app: &tauri::AppHandle, config: &ProxyConfig) -> Result<(), String> {
+    let mut settings = load_pac_settings(app)?;
+    let uses_local = config.auto_config_url.trim().starts_with(&pac::base_url(settings.port));
+    if settings.enabled == uses_local {
+        return Ok(());
+    }
+    settings.enabled = uses_local;
+    storage::save_json(&storage::app_data_file(app, PAC_SETTINGS_FILE)?, &settings)?;
+    apply_pac_settings(&settings, settings.port).await.map(|_| ())
+}
@@ -0,0 +1,234 @@
+//! 代理绕过列表（`ProxyOverride`）
+//!
+//! 每一项可以是精确主机名、通配符（`*.example.com`、`192.168.*`）、IP 地址、
+//! CIDR 网段或 `<local>`（不含点的主机名）。WinINet 不支持 CIDR，
+//! 写入注册表时按字节边界展开为通配符（`172.16.0.0/12` → `172.16.*` … `172.31.*`）。
+
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "lowercase")]
+pub enum BypassRule {
+    Exact(String),
+    Wildcard(String),
+    Ip(IpAddr),
+    Cidr { network: Ipv4Addr, prefix: u8 },
+    /// `<local>`
+    Local,
+}
+
+/// 单项的校验结果，供编辑器逐项提示
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BypassCheck {
+    pub input: String,
+    pub rule: Option<BypassRule>,
+    /// 写入注册表的形式
+    pub entries: Vec<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BypassPreset {
+    pub id: String,
+    pub rules: Vec<String>,
+}
+
+impl BypassRule {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let value = input.trim();
+        if value.eq_ignore_ascii_case("<local>") {
+            return Ok(Self::Local);
+        }
+        if let Some((network, prefix)) = value.split_once('/') {
+            let network: Ipv4Addr = network
+                .trim()
+                .parse()
+                .map_err(|_| format!("无效的网段: {}（仅支持 IPv4）", value))?;
+            let prefix: u8 = prefix
+                .trim()
+                .parse()
+                .ok()
+                .filter(|p| *p <= 32)
+                .ok_or_else(|| format!("无效的网段前缀: {}", value))?;
+            // 去掉主机位，如 192.168.1.10/24 → 192.168.1.0/24
+            let mask = u32::MAX.checked_shl(32 - prefix as u32).unwrap_or(0);
+            let network = Ipv4Addr::from(u32::from(network) & mask);
+            return Ok(Self::Cidr { network, prefix });
+        }
+        if let Ok(ip) = value.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+            return Ok(Self::Ip(ip));
+        }
+
+        let host = value.to_lowercase();
+        let valid_chars = host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '*'));
+        if host.is_empty() || !valid_chars || host.contains("..") {
+            return Err(format!("无效的绕过项: {}", value));
+        }
+        Ok(if host.contains('*') {
+            Self::Wildcard(host)
+        } else {
+            Self::Exact(host)
+        })
+    }
+
+    /// 写入 `ProxyOverride` 的形式
+    pub fn to_registry_entries(&self) -> Vec<String> {
+        match self {
+            Self::Exact(host) | Self::Wildcard(host) => vec![host.clone()],
+            Self::Ip(IpAddr::V4(ip)) => vec![ip.to_string()],
+            Self::Ip(IpAddr::V6(ip)) => vec![format!("[{}]", ip)],
+            Self::Local => vec!["<local>".to_string()],
+            Self::Cidr { network, prefix } => expand_cidr(*network, *prefix),
+        }
+    }
+}
+
+/// 把 CIDR 按字节边界展开为通配符
+fn expand_cidr(network: Ipv4Addr, prefix: u8) -> Vec<String> {
+    if prefix == 32 {
+        return vec![network.to_string()];
+    }
+    // 向上取整到字节边界，再枚举中间的取值（最多 128 个）
+    let boundary = prefix.div_ceil(8) * 8;
+    let count = 1u32 << (boundary - prefix);
+    let fixed_octets = (boundary / 8) as usize;
+    let step = if boundary == 0 { 0 } else { 1u32 << (32 - boundary as u32) };
+
+    (0..count)
+        .map(|i| {
+            let base = Ipv4Addr::from(u32::from(network) + i * step).octets();
+            let mut parts: Vec<String> = base[..fixed_octets].iter().map(u8::to_string).collect();
+            if fixed_octets < 4 {
+                parts.push("*".to_string());
+            }
+            parts.join(".")
+        })
+        .collect()
+}
+
+/// 校验每一项
+pub fn check(inputs: &[String]) -> Vec<BypassCheck> {
+    inputs
+        .iter()
+        .filter(|input| !input.trim().is_empty())
+        .map(|input| match BypassRule::parse(input) {
+            Ok(rule) => BypassCheck {
+                input: input.trim().to_string(),
+                entries: rule.to_registry_entries(),
+                rule: Some(rule),
+                error: None,
+            },
+            Err(e) => BypassCheck {
+                input: input.trim().to_string(),
+                rule: None,
+                entries: Vec::new(),
+                error: Some(e),
+            },
+        })
+        .collect()
+}
+
+/// 校验并转换为写入注册表的列表（去重，忽略空项）
+pub fn to_registry_list(inputs: &[String]) -> Result<Vec<String>, String> {
+    let mut entries: Vec<String> = Vec::new();
+    for item in check(inputs) {
+        if let Some(error) = item.error {
+            return Err(error);
+        }
+        for entry in item.entries {
+            if !entries.iter().any(|e| e.eq_ignore_ascii_case(&entry)) {
+                entries.push(entry);
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// 解析注册表中的 `ProxyOverride`，忽略空项
+pub fn parse_registry(value: &str) -> Vec<String> {
+    value
+        .split(';')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// 常用的绕过列表
+pub fn presets() -> Vec<BypassPreset> {
+    let preset = |id: &str, rules: &[&str]| BypassPreset {
+        id: id.to_string(),
+        rules: rules.iter().map(|r| r.to_string()).collect(),
+    };
+    vec![
+        preset(
+            "private",
+            &[
+                "<local>",
+                "localhost",
+                "127.0.0.1",
+                "::1",
+                "10.0.0.0/8",
+                "172.16.0.0/12",
+                "192.168.0.0/16",
+                "169.254.0.0/16",
+            ],
+        ),
+        preset(
+            "corporate",
+            &["*.local", "*.lan", "*.corp", "*.internal", "*.intranet", "*.home.arpa"],
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bypass_rules() {
+        assert_eq!(BypassRule::parse("<LOCAL>").unwrap(), BypassRule::Local);
+        assert_eq!(BypassRule::parse(" Intranet ").unwrap(), BypassRule::Exact("intranet".to_string()));
+        assert_eq!(BypassRule::parse("*.Corp.example").unwrap(), BypassRule::Wildcard("*.corp.example".to_string()));
+        assert_eq!(
+            BypassRule::parse("192.168.1.10/24").unwrap(),
+            BypassRule::Cidr { network: Ipv4Addr::new(192, 168, 1, 0), prefix: 24 }
+        );
+        assert_eq!(
+            BypassRule::parse("[::1]").unwrap().to_registry_entries(),
+            vec!["[::1]".to_string()]
+        );
+        for bad in ["", "a b", "10.0.0.0/33", "fe80::/10", "bad..host", "http://x"] {
+            assert!(BypassRule::parse(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_cidr_expansion_and_registry_list() {
+        assert_eq!(expand_cidr(Ipv4Addr::new(10, 0, 0, 0), 8), vec!["10.*"]);
+        assert_eq!(expand_cidr(Ipv4Addr::new(192, 168, 1, 0), 24), vec!["192.168.1.*"]);
+        assert_eq!(expand_cidr(Ipv4Addr::new(0, 0, 0, 0), 0), vec!["*"]);
+        assert_eq!(expand_cidr(Ipv4Addr::new(10, 1, 2, 3), 32), vec!["10.1.2.3"]);
+        let private = expand_cidr(Ipv4Addr::new(172, 16, 0, 0), 12);
+        assert_eq!(private.len(), 16);
+        assert_eq!((private[0].as_str(), private[15].as_str()), ("172.16.*", "172.31.*"));
+        assert_eq!(expand_cidr(Ipv4Addr::new(192, 168, 4, 0), 23), vec!["192.168.4.*", "192.168.5.*"]);
+
+        let inputs: Vec<String> = ["localhost", "", "10.0.0.0/8", "LOCALHOST", "<local>"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(to_registry_list(&inputs).unwrap(), vec!["localhost", "10.*", "<local>"]);
+        assert!(to_registry_list(&["a b".to_string()]).is_err());
+        assert_eq!(parse_registry("localhost;; 10.* ;"), vec!["localhost", "10.*"]);
+
+        for preset in presets() {
+            assert!(to_registry_list(&preset.rules).is_ok(), "{}", preset.id);
+        }
+    }
+}
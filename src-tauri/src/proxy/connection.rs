@@ -0,0 +1,268 @@
+//! `Connections\DefaultConnectionSettings` 二进制设置
+//!
+//! 新版 Windows 以这个值为准，`ProxyEnable`/`ProxyServer` 等只是旧版兼容值，只改旧值时
+//! 有时要重启才生效。结构（均为小端）：
+//!
+//! ```text
+//! u32 版本（通常为 0x46）
+//! u32 修改计数（每次写入加一）
+//! u32 标志（PROXY_TYPE_*）
+//! u32 长度 + 代理服务器
+//! u32 长度 + 绕过列表
+//! u32 长度 + 自动配置脚本地址
+//! 其余字节（自动检测的缓存结果），原样保留
+//! ```
+
+use super::{ProxyConfig, ProxyServers};
+
+pub const VALUE_NAME: &str = "DefaultConnectionSettings";
+
+pub const DEFAULT_VERSION: u32 = 0x46;
+pub const PROXY_TYPE_DIRECT: u32 = 0x01;
+pub const PROXY_TYPE_PROXY: u32 = 0x02;
+pub const PROXY_TYPE_AUTO_PROXY_URL: u32 = 0x04;
+pub const PROXY_TYPE_AUTO_DETECT: u32 = 0x08;
+
+/// 新建设置时末尾填充的空字节数
+const DEFAULT_TRAILER_LEN: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectionSettings {
+    pub version: u32,
+    pub counter: u32,
+    pub flags: u32,
+    pub proxy_server: String,
+    pub bypass: String,
+    pub auto_config_url: String,
+    pub trailer: Vec<u8>,
+}
+
+impl Default for ConnectionSettings {
+    fn default() -> Self {
+        Self {
+            version: DEFAULT_VERSION,
+            counter: 0,
+            flags: PROXY_TYPE_DIRECT,
+            proxy_server: String::new(),
+            bypass: String::new(),
+            auto_config_url: String::new(),
+            trailer: vec![0; DEFAULT_TRAILER_LEN],
+        }
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl Reader<'_> {
+    fn take(&mut self, len: usize, what: &str) -> Result<&[u8], String> {
+        let end = self
+            .offset
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| format!("连接设置数据不完整: 缺少{}", what))?;
+        let slice = &self.bytes[self.offset..end];
+        self.offset = end;
+        Ok(slice)
+    }
+
+    fn u32(&mut self, what: &str) -> Result<u32, String> {
+        let bytes = self.take(4, what)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn string(&mut self, what: &str) -> Result<String, String> {
+        let len = self.u32(what)? as usize;
+        Ok(String::from_utf8_lossy(self.take(len, what)?).to_string())
+    }
+}
+
+impl ConnectionSettings {
+    pub fn parse(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = Reader { bytes, offset: 0 };
+        let version = reader.u32("版本")?;
+        let counter = reader.u32("修改计数")?;
+        let flags = reader.u32("标志")?;
+        let proxy_server = reader.string("代理服务器")?;
+        let bypass = reader.string("绕过列表")?;
+        let auto_config_url = reader.string("自动配置脚本地址")?;
+        Ok(Self {
+            version,
+            counter,
+            flags,
+            proxy_server,
+            bypass,
+            auto_config_url,
+            trailer: bytes[reader.offset..].to_vec(),
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for value in [self.version, self.counter, self.flags] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        for text in [&self.proxy_server, &self.bypass, &self.auto_config_url] {
+            bytes.extend_from_slice(&(text.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(text.as_bytes());
+        }
+        bytes.extend_from_slice(&self.trailer);
+        bytes
+    }
+
+    fn set_flag(&mut self, flag: u32, on: bool) {
+        if on {
+            self.flags |= flag;
+        } else {
+            self.flags &= !flag;
+        }
+    }
+
+    /// 按（已校验的）设置更新。与旧版值一致：关闭代理时保留原来的服务器与绕过列表
+    pub fn apply(&mut self, config: &ProxyConfig) {
+        self.flags |= PROXY_TYPE_DIRECT;
+        self.set_flag(PROXY_TYPE_PROXY, config.enabled);
+        self.set_flag(PROXY_TYPE_AUTO_DETECT, config.auto_detect);
+        if config.enabled {
+            self.proxy_server = config.servers.to_registry_string();
+            self.bypass = config.bypass.join(";");
+        }
+        self.set_auto_config_url(Some(config.auto_config_url.trim()).filter(|url| !url.is_empty()));
+    }
+
+    /// 设置（`None` 时清除）自动配置脚本地址
+    pub fn set_auto_config_url(&mut self, url: Option<&str>) {
+        self.set_flag(PROXY_TYPE_AUTO_PROXY_URL, url.is_some());
+        self.auto_config_url = url.unwrap_or_default().to_string();
+    }
+
+    /// 修改计数加一，系统据此判断设置是否变化
+    pub fn bump_counter(&mut self) {
+        self.counter = self.counter.wrapping_add(1);
+    }
+
+    pub fn to_config(&self) -> ProxyConfig {
+        ProxyConfig {
+            enabled: self.flags & PROXY_TYPE_PROXY != 0,
+            servers: ProxyServers::parse(&self.proxy_server),
+            bypass: super::bypass::parse_registry(&self.bypass),
+            auto_detect: self.flags & PROXY_TYPE_AUTO_DETECT != 0,
+            // 关闭“使用设置脚本”时系统会保留地址，只清除标志
+            auto_config_url: if self.flags & PROXY_TYPE_AUTO_PROXY_URL != 0 {
+                self.auto_config_url.clone()
+            } else {
+                String::new()
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 以下样例是 `reg export` 格式的文本，可以直接替换为真机导出的内容。目前的数据按字段布局
+    // 手工构造，不是真机导出的（尚缺 Windows 10/11 的实际导出样本）。末尾字节模拟自动检测的
+    // 缓存结果，内容不解析，只检查能否原样写回。
+
+    /// 样例：代理 127.0.0.1:7890 并勾选“自动检测设置”
+    const SAMPLE_PROXY: &str = r#""DefaultConnectionSettings"=hex:46,00,00,00,1c,00,00,00,0b,00,00,00,0e,00,00,\
+  00,31,32,37,2e,30,2e,30,2e,31,3a,37,38,39,30,16,00,00,00,6c,6f,63,61,6c,68,\
+  6f,73,74,3b,31,30,2e,2a,3b,3c,6c,6f,63,61,6c,3e,00,00,00,00,01,00,00,00,c0,\
+  a8,01,14,80,3e,6b,1f,92,1b,db,01,00,00,00,00,00,00,00,00,00,00,00,00,00,00,\
+  00,00"#;
+
+    /// 样例：只勾选“使用设置脚本”（代理已关闭，但保留了原来的服务器）
+    const SAMPLE_PAC: &str = r#""DefaultConnectionSettings"=hex:46,00,00,00,07,01,00,00,05,00,00,00,0e,00,00,\
+  00,31,32,37,2e,30,2e,30,2e,31,3a,37,38,39,30,00,00,00,00,21,00,00,00,68,74,\
+  74,70,3a,2f,2f,77,70,61,64,2e,63,6f,72,70,2e,65,78,61,6d,70,6c,65,2f,77,70,\
+  61,64,2e,64,61,74,02,00,00,00,0a,00,00,05,0a,00,00,06,40,5d,2e,7a,8c,1b,db,\
+  01,00,00,00,00,00,00,00,00,00,00,00,00"#;
+
+    /// 样例：只勾选“自动检测设置”
+    const SAMPLE_AUTO_DETECT: &str = r#""DefaultConnectionSettings"=hex:46,00,00,00,31,00,00,00,09,00,00,00,00,00,00,\
+  00,00,00,00,00,00,00,00,00,02,00,00,00,c0,a8,01,14,0a,00,00,05,70,1c,4f,3e,\
+  9d,1b,db,01,00,00,00,00,00,00,00,00,00,00,00,00"#;
+
+    /// 解析 `reg export` 中的 `"名称"=hex:..` 值
+    fn reg_hex(export: &str) -> Vec<u8> {
+        let (_, hex) = export.split_once("=hex:").expect("不是 hex 值");
+        hex.split(',')
+            .map(|b| b.trim_matches(|c: char| c == '\\' || c.is_whitespace()))
+            .map(|b| u8::from_str_radix(b, 16).expect("无效的字节"))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_sample_settings() {
+        let proxy = ConnectionSettings::parse(&reg_hex(SAMPLE_PROXY)).unwrap();
+        assert_eq!((proxy.version, proxy.counter, proxy.flags), (0x46, 0x1c, 0x0b));
+        assert_eq!(proxy.bypass, "localhost;10.*;<local>");
+        assert_eq!(proxy.trailer.len(), 32);
+        assert_eq!(&proxy.trailer[..8], &[0x01, 0x00, 0x00, 0x00, 0xc0, 0xa8, 0x01, 0x14]);
+        let config = proxy.to_config();
+        assert!(config.enabled && config.auto_detect);
+        assert_eq!(config.servers.all.as_deref(), Some("127.0.0.1:7890"));
+        assert_eq!(config.auto_config_url, "");
+
+        let pac = ConnectionSettings::parse(&reg_hex(SAMPLE_PAC)).unwrap();
+        let config = pac.to_config();
+        assert!(!config.enabled && !config.auto_detect);
+        assert_eq!(config.auto_config_url, "http://wpad.corp.example/wpad.dat");
+        assert_eq!(pac.proxy_server, "127.0.0.1:7890");
+        assert!(pac.trailer.iter().any(|b| *b != 0));
+
+        let auto = ConnectionSettings::parse(&reg_hex(SAMPLE_AUTO_DETECT)).unwrap();
+        assert_eq!(auto.flags, PROXY_TYPE_DIRECT | PROXY_TYPE_AUTO_DETECT);
+        let config = auto.to_config();
+        assert!(!config.enabled && config.auto_detect);
+        assert_eq!((auto.proxy_server.as_str(), auto.auto_config_url.as_str()), ("", ""));
+
+        let bytes = reg_hex(SAMPLE_PROXY);
+        assert!(ConnectionSettings::parse(&bytes[..20]).is_err());
+        assert!(ConnectionSettings::parse(&[]).is_err());
+    }
+
+    #[test]
+    fn test_samples_round_trip() {
+        for sample in [SAMPLE_PROXY, SAMPLE_PAC, SAMPLE_AUTO_DETECT] {
+            let bytes = reg_hex(sample);
+            let settings = ConnectionSettings::parse(&bytes).unwrap();
+            assert_eq!(settings.to_bytes(), bytes);
+        }
+    }
+
+    #[test]
+    fn test_apply_config() {
+        let sample = reg_hex(SAMPLE_PROXY);
+        let mut settings = ConnectionSettings::parse(&sample).unwrap();
+        settings.apply(&ProxyConfig {
+            enabled: false,
+            auto_config_url: " http://pac/ ".to_string(),
+            ..Default::default()
+        });
+        settings.bump_counter();
+        assert_eq!(settings.counter, 0x1d);
+        assert_eq!(settings.flags, PROXY_TYPE_DIRECT | PROXY_TYPE_AUTO_PROXY_URL);
+        // 关闭代理时保留服务器
+        assert_eq!(settings.proxy_server, "127.0.0.1:7890");
+        assert_eq!(settings.auto_config_url, "http://pac/");
+        // 自动检测的缓存结果保持不变
+        assert_eq!(settings.trailer, &sample[sample.len() - 32..]);
+
+        let config = ProxyConfig {
+            enabled: true,
+            servers: ProxyServers::parse("http=10.0.0.1:8080;socks=10.0.0.1:1080"),
+            bypass: vec!["<local>".to_string()],
+            auto_detect: false,
+            auto_config_url: String::new(),
+        };
+        let mut settings = ConnectionSettings::default();
+        settings.apply(&config);
+        let parsed = ConnectionSettings::parse(&settings.to_bytes()).unwrap();
+        assert_eq!(parsed.flags, PROXY_TYPE_DIRECT | PROXY_TYPE_PROXY);
+        assert_eq!(parsed.to_config(), config);
+    }
+}
@@ -0,0 +1,225 @@
+//! 自动配置脚本（PAC）
+//!
+//! 脚本可以由用户直接编写，也可以由绕过列表与按域名指定的代理规则生成。
+//! 生成的脚本由本机回环地址上的 HTTP 服务提供（见 `pac_server`），
+//! 系统的 `AutoConfigURL` 指向该服务，因此不需要另外托管 PAC 文件。
+
+use super::bypass::BypassRule;
+use serde::{Deserialize, Serialize};
+use std::net::Ipv4Addr;
+
+/// 默认监听端口
+pub const DEFAULT_PORT: u16 = 18086;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PacMode {
+    /// 由规则生成
+    #[default]
+    Generated,
+    /// 用户编写的脚本
+    Custom,
+}
+
+/// 按域名指定代理的规则
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PacRule {
+    /// 主机名或通配符（如 `*.example.com`）
+    pub pattern: String,
+    /// PAC 返回值，如 `PROXY 127.0.0.1:7890`、`SOCKS5 127.0.0.1:1080`、`DIRECT`；
+    /// 只填 `host:port` 时按 `PROXY host:port` 处理
+    pub proxy: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PacSettings {
+    pub enabled: bool,
+    pub port: u16,
+    pub mode: PacMode,
+    /// 自定义脚本（`mode` 为 custom 时使用）
+    pub script: String,
+    pub rules: Vec<PacRule>,
+    /// 直连的主机（支持通配符、CIDR 网段与 `<local>`）
+    pub bypass: Vec<String>,
+    /// 其他请求使用的代理，为空时直连
+    pub default_proxy: String,
+}
+
+impl Default for PacSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_PORT,
+            mode: PacMode::Generated,
+            script: String::new(),
+            rules: Vec::new(),
+            bypass: Vec::new(),
+            default_proxy: String::new(),
+        }
+    }
+}
+
+impl PacSettings {
+    /// 当前应提供的脚本
+    pub fn script(&self) -> Result<String, String> {
+        match self.mode {
+            PacMode::Custom => {
+                if !self.script.contains("FindProxyForURL") {
+                    return Err("PAC 脚本中缺少 FindProxyForURL 函数".to_string());
+                }
+                Ok(self.script.clone())
+            }
+            PacMode::Generated => generate(&self.rules, &self.bypass, &self.default_proxy),
+        }
+    }
+}
+
+/// 系统 `AutoConfigURL` 使用的地址。
+/// 带上脚本的哈希，脚本变化后地址随之变化，避免系统继续使用缓存的旧脚本。
+/// 地址保存在系统设置中，因此使用跨版本稳定的哈希
+pub fn script_url(port: u16, script: &str) -> String {
+    let version = crate::storage::stable_hash(script.as_bytes());
+    format!("{}?v={:016x}", base_url(port), version)
+}
+
+/// 不带版本参数的地址，用于判断 `AutoConfigURL` 是否指向本应用
+pub fn base_url(port: u16) -> String {
+    format!("http://127.0.0.1:{}/proxy.pac", port)
+}
+
+/// 由绕过列表与规则生成脚本：先匹配绕过列表，再按顺序匹配规则，最后使用默认代理
+pub fn generate(rules: &[PacRule], bypass: &[String], default_proxy: &str) -> Result<String, String> {
+    let mut script = String::from("function FindProxyForURL(url, host) {\n");
+
+    for entry in bypass.iter().map(|e| e.trim()).filter(|e| !e.is_empty()) {
+        script.push_str(&format!("  if ({}) return \"DIRECT\";\n", host_condition(entry)));
+    }
+
+    for rule in rules {
+        let pattern = rule.pattern.trim();
+        if pattern.is_empty() {
+            return Err("PAC 规则的主机不能为空".to_string());
+        }
+        script.push_str(&format!(
+            "  if ({}) return {};\n",
+            host_condition(pattern),
+            js_string(&proxy_result(&rule.proxy)?)
+        ));
+    }
+
+    let fallback = if default_proxy.trim().is_empty() {
+        "DIRECT".to_string()
+    } else {
+        format!("{}; DIRECT", proxy_result(default_proxy)?)
+    };
+    script.push_str(&format!("  return {};\n}}\n", js_string(&fallback)));
+    Ok(script)
+}
+
+/// 判断主机是否匹配的 JavaScript 表达式
+fn host_condition(pattern: &str) -> String {
+    match BypassRule::parse(pattern) {
+        Ok(BypassRule::Local) => return "isPlainHostName(host)".to_string(),
+        Ok(BypassRule::Cidr { network, prefix }) => {
+            let mask = Ipv4Addr::from(u32::MAX.checked_shl(32 - prefix as u32).unwrap_or(0));
+            return format!(
+                "isInNet(host, {}, {})",
+                js_string(&network.to_string()),
+                js_string(&mask.to_string())
+            );
+        }
+        _ => {}
+    }
+    let pattern = pattern.to_lowercase();
+    if pattern.contains('*') || pattern.contains('?') {
+        format!("shExpMatch(host, {})", js_string(&pattern))
+    } else {
+        format!("host === {}", js_string(&pattern))
+    }
+}
+
+/// 规范化 PAC 返回值，`host:port` 补全为 `PROXY host:port`
+fn proxy_result(value: &str) -> Result<String, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err("PAC 规则的代理不能为空".to_string());
+    }
+    let items: Vec<String> = value
+        .split(';')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            let keyword = item.split_whitespace().next().unwrap_or("").to_uppercase();
+            if matches!(keyword.as_str(), "DIRECT" | "PROXY" | "SOCKS" | "SOCKS4" | "SOCKS5" | "HTTP" | "HTTPS") {
+                Ok(item.to_string())
+            } else if item.contains(char::is_whitespace) {
+                Err(format!("无效的 PAC 代理: {}", item))
+            } else {
+                Ok(format!("PROXY {}", item))
+            }
+        })
+        .collect::<Result<_, String>>()?;
+    Ok(items.join("; "))
+}
+
+/// 转为 JavaScript 字符串字面量（JSON 字符串同时是合法的 JS 字符串）
+fn js_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_pac_script() {
+        let rules = vec![
+            PacRule {
+                pattern: "*.Google.com".to_string(),
+                proxy: "127.0.0.1:7890".to_string(),
+            },
+            PacRule {
+                pattern: "intranet".to_string(),
+                proxy: "SOCKS5 10.0.0.1:1080; DIRECT".to_string(),
+            },
+        ];
+        let bypass = vec![
+            "<local>".to_string(),
+            "*.corp.example".to_string(),
+            "10.0.0.0/8".to_string(),
+            " ".to_string(),
+        ];
+        let script = generate(&rules, &bypass, "proxy.example:8080").unwrap();
+        assert_eq!(
+            script,
+            "function FindProxyForURL(url, host) {\n\
+             \x20 if (isPlainHostName(host)) return \"DIRECT\";\n\
+             \x20 if (shExpMatch(host, \"*.corp.example\")) return \"DIRECT\";\n\
+             \x20 if (isInNet(host, \"10.0.0.0\", \"255.0.0.0\")) return \"DIRECT\";\n\
+             \x20 if (shExpMatch(host, \"*.google.com\")) return \"PROXY 127.0.0.1:7890\";\n\
+             \x20 if (host === \"intranet\") return \"SOCKS5 10.0.0.1:1080; DIRECT\";\n\
+             \x20 return \"PROXY proxy.example:8080; DIRECT\";\n\
+             }\n"
+        );
+
+        assert!(generate(&rules, &[], "bad proxy value").is_err());
+        assert!(generate(&[], &[], "").unwrap().contains("return \"DIRECT\";"));
+    }
+
+    #[test]
+    fn test_script_url_changes_with_content() {
+        let a = script_url(DEFAULT_PORT, "function FindProxyForURL() { return \"DIRECT\"; }");
+        let b = script_url(DEFAULT_PORT, "function FindProxyForURL() { return \"PROXY a:1\"; }");
+        assert_eq!(a, "http://127.0.0.1:18086/proxy.pac?v=99172422d7852c2f");
+        assert_ne!(a, b);
+
+        let custom = PacSettings {
+            mode: PacMode::Custom,
+            script: "var x = 1;".to_string(),
+            ..Default::default()
+        };
+        assert!(custom.script().is_err());
+    }
+}
@@ -0,0 +1,290 @@
+//! PAC 脚本求值
+//!
+//! 在内嵌的 JavaScript 引擎中执行 `FindProxyForURL(url, host)`，用于检查某个地址会走哪个代理。
+//! 预先定义了常用的 PAC 辅助函数（`isPlainHostName`、`dnsDomainIs`、`localHostOrDomainIs`、
+//! `isResolvable`、`isInNet`、`dnsResolve`、`myIpAddress`、`dnsDomainLevels`、`shExpMatch`）；
+//! 与时间相关的 `weekdayRange` 等函数未提供，脚本调用时会报错。
+
+use boa_engine::property::Attribute;
+use boa_engine::{js_string, Context, JsResult, JsString, JsValue, NativeFunction, Source};
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, ToSocketAddrs, UdpSocket};
+
+/// `dnsResolve` 使用的解析函数：返回主机名的 IPv4 地址
+pub type Resolver = fn(&str) -> Option<String>;
+
+/// 单个脚本允许的最大循环次数，防止死循环卡住
+const LOOP_ITERATION_LIMIT: u64 = 1_000_000;
+
+/// 辅助函数（实现参考 Mozilla 的 PAC 实现）
+const PRELUDE: &str = r#"
+function isPlainHostName(host) {
+  return host.indexOf('.') < 0;
+}
+function dnsDomainIs(host, domain) {
+  host = String(host).toLowerCase();
+  domain = String(domain).toLowerCase();
+  return host.length >= domain.length && host.substring(host.length - domain.length) === domain;
+}
+function localHostOrDomainIs(host, hostdom) {
+  return host === hostdom || hostdom.lastIndexOf(host + '.', 0) === 0;
+}
+function isResolvable(host) {
+  return dnsResolve(host) !== null;
+}
+function convert_addr(ipchars) {
+  var bytes = String(ipchars).split('.');
+  return (((bytes[0] & 0xff) << 24) | ((bytes[1] & 0xff) << 16) | ((bytes[2] & 0xff) << 8) | (bytes[3] & 0xff)) >>> 0;
+}
+function isInNet(ipaddr, pattern, maskstr) {
+  var ip = /^\d+\.\d+\.\d+\.\d+$/.test(ipaddr) ? ipaddr : dnsResolve(ipaddr);
+  if (ip === null) {
+    return false;
+  }
+  var mask = convert_addr(maskstr);
+  return ((convert_addr(ip) & mask) >>> 0) === ((convert_addr(pattern) & mask) >>> 0);
+}
+function dnsDomainLevels(host) {
+  return String(host).split('.').length - 1;
+}
+function shExpMatch(str, shexp) {
+  var re = String(shexp)
+    .replace(/[.+^${}()|[\]\\]/g, '\\$&')
+    .replace(/\*/g, '.*')
+    .replace(/\?/g, '.');
+  return new RegExp('^' + re + '$').test(str);
+}
+function alert(message) {}
+"#;
+
+/// 结果中的一项
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyHop {
+    /// `DIRECT`、`PROXY`、`SOCKS`、`SOCKS5`、`HTTPS` 等
+    pub kind: String,
+    /// 代理地址，直连时为空
+    pub server: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PacEvaluation {
+    pub url: String,
+    pub host: String,
+    /// `FindProxyForURL` 的原始返回值
+    pub result: String,
+    /// 按顺序尝试的代理
+    pub chain: Vec<ProxyHop>,
+}
+
+/// 执行脚本中的 `FindProxyForURL`，主机名由 `resolve` 解析
+pub fn evaluate(
+    script: &str,
+    url: &str,
+    host: &str,
+    my_ip: &str,
+    resolve: Resolver,
+) -> Result<PacEvaluation, String> {
+    let mut context = Context::default();
+    context
+        .runtime_limits_mut()
+        .set_loop_iteration_limit(LOOP_ITERATION_LIMIT);
+
+    context
+        .register_global_builtin_callable(
+            js_string!("dnsResolve"),
+            1,
+            NativeFunction::from_copy_closure(move |_this, args, context| dns_resolve(args, context, resolve)),
+        )
+        .map_err(|e| format!("初始化 PAC 环境失败: {}", e))?;
+    context
+        .register_global_property(js_string!("__myIpAddress"), JsString::from(my_ip), Attribute::all())
+        .map_err(|e| format!("初始化 PAC 环境失败: {}", e))?;
+    context
+        .eval(Source::from_bytes(PRELUDE))
+        .and_then(|_| context.eval(Source::from_bytes("function myIpAddress() { return __myIpAddress; }")))
+        .map_err(|e| format!("初始化 PAC 环境失败: {}", e))?;
+
+    context
+        .eval(Source::from_bytes(script))
+        .map_err(|e| format!("PAC 脚本执行失败: {}", e))?;
+
+    let function = context
+        .global_object()
+        .get(js_string!("FindProxyForURL"), &mut context)
+        .map_err(|e| format!("PAC 脚本执行失败: {}", e))?;
+    let function = function
+        .as_callable()
+        .ok_or_else(|| "PAC 脚本中缺少 FindProxyForURL 函数".to_string())?;
+
+    let result = function
+        .call(
+            &JsValue::undefined(),
+            &[JsString::from(url).into(), JsString::from(host).into()],
+            &mut context,
+        )
+        .and_then(|value| value.to_string(&mut context))
+        .map_err(|e| format!("FindProxyForURL 执行失败: {}", e))?
+        .to_std_string_escaped();
+
+    Ok(PacEvaluation {
+        url: url.to_string(),
+        host: host.to_string(),
+        chain: parse_result(&result),
+        result,
+    })
+}
+
+/// 解析 `PROXY a:1; SOCKS5 b:2; DIRECT` 形式的返回值
+pub fn parse_result(result: &str) -> Vec<ProxyHop> {
+    result
+        .split(';')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            let mut parts = item.split_whitespace();
+            ProxyHop {
+                kind: parts.next().unwrap_or("").to_uppercase(),
+                server: parts.next().map(str::to_string),
+            }
+        })
+        .collect()
+}
+
+/// 从 URL 中取出主机名（也可直接传入主机名）。返回 `(完整 URL, 主机名)`
+pub fn split_url(input: &str) -> Result<(String, String), String> {
+    let input = input.trim();
+    let url = if input.contains("://") {
+        input.to_string()
+    } else {
+        format!("http://{}/", input)
+    };
+    let parsed = reqwest::Url::parse(&url).map_err(|e| format!("无效的地址 {}: {}", input, e))?;
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| format!("地址中缺少主机名: {}", input))?
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_string();
+    Ok((url, host))
+}
+
+/// 本机用于访问外网的地址（不会真正发送数据），获取失败时为 127.0.0.1
+pub fn local_ip_address() -> String {
+    UdpSocket::bind("0.0.0.0:0")
+        .and_then(|socket| {
+            socket.connect("8.8.8.8:80")?;
+            socket.local_addr()
+        })
+        .map(|addr| addr.ip().to_string())
+        .unwrap_or_else(|_| "127.0.0.1".to_string())
+}
+
+/// 通过系统 DNS 解析，返回第一个 IPv4 地址
+pub fn system_resolve(host: &str) -> Option<String> {
+    (host, 0)
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.find(|a| a.is_ipv4()))
+        .map(|addr| addr.ip().to_string())
+}
+
+/// `dnsResolve(host)`：返回第一个 IPv4 地址，无法解析时返回 null
+fn dns_resolve(args: &[JsValue], context: &mut Context, resolve: Resolver) -> JsResult<JsValue> {
+    let host = match args.first() {
+        Some(value) => value.to_string(context)?.to_std_string_escaped(),
+        None => return Ok(JsValue::null()),
+    };
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        return Ok(JsString::from(ip.to_string()).into());
+    }
+    Ok(resolve(&host).map_or(JsValue::null(), |ip| JsString::from(ip).into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 固定的解析结果，测试不访问网络
+    fn fake_resolve(host: &str) -> Option<String> {
+        match host {
+            "build.corp.example" => Some("10.2.3.4".to_string()),
+            "wiki.corp.example" => Some("172.16.0.5".to_string()),
+            _ => None,
+        }
+    }
+
+    fn eval_script(script: &str, url: &str, host: &str, my_ip: &str) -> Result<PacEvaluation, String> {
+        evaluate(script, url, host, my_ip, fake_resolve)
+    }
+
+    const SCRIPT: &str = r#"
+        function FindProxyForURL(url, host) {
+          if (isPlainHostName(host) || isInNet(host, "10.0.0.0", "255.0.0.0")) return "DIRECT";
+          if (dnsDomainIs(host, ".corp.example") && myIpAddress() === "192.168.1.20") return "PROXY corp:3128";
+          if (shExpMatch(url, "https://*.google.com/*")) return "SOCKS5 127.0.0.1:1080; PROXY 127.0.0.1:7890; DIRECT";
+          return "DIRECT";
+        }
+    "#;
+
+    #[test]
+    fn test_evaluate_pac_script() {
+        let eval = |url: &str| {
+            let (url, host) = split_url(url).unwrap();
+            eval_script(SCRIPT, &url, &host, "192.168.1.20").unwrap()
+        };
+
+        assert_eq!(eval("intranet").result, "DIRECT");
+        assert_eq!(eval("http://10.1.2.3:8080/x").result, "DIRECT");
+        // 解析到 10.0.0.0/8 的主机名直连
+        assert_eq!(eval("build.corp.example").result, "DIRECT");
+        assert_eq!(eval("wiki.corp.example").result, "PROXY corp:3128");
+
+        let google = eval("https://www.google.com/search?q=pac");
+        assert_eq!(google.host, "www.google.com");
+        assert_eq!(
+            google.chain,
+            vec![
+                ProxyHop { kind: "SOCKS5".to_string(), server: Some("127.0.0.1:1080".to_string()) },
+                ProxyHop { kind: "PROXY".to_string(), server: Some("127.0.0.1:7890".to_string()) },
+                ProxyHop { kind: "DIRECT".to_string(), server: None },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_evaluate_generated_script() {
+        let rules = vec![crate::proxy::PacRule {
+            pattern: "*.example.com".to_string(),
+            proxy: "127.0.0.1:7890".to_string(),
+        }];
+        let script = crate::proxy::pac::generate(&rules, &["<local>".to_string()], "").unwrap();
+        let eval = |host: &str| eval_script(&script, &format!("http://{}/", host), host, "127.0.0.1").unwrap().result;
+        assert_eq!(eval("www.example.com"), "PROXY 127.0.0.1:7890");
+        assert_eq!(eval("printer"), "DIRECT");
+        assert_eq!(eval("example.org"), "DIRECT");
+    }
+
+    #[test]
+    fn test_evaluate_reports_script_errors() {
+        assert!(eval_script("var x = 1;", "http://a/", "a", "127.0.0.1")
+            .unwrap_err()
+            .contains("FindProxyForURL"));
+        assert!(eval_script("function FindProxyForURL(url, host) {", "http://a/", "a", "127.0.0.1").is_err());
+        assert!(eval_script(
+            "function FindProxyForURL(url, host) { while (true) {} }",
+            "http://a/",
+            "a",
+            "127.0.0.1"
+        )
+        .is_err());
+        assert!(eval_script(
+            "function FindProxyForURL(url, host) { return weekdayRange('MON', 'FRI') ? 'DIRECT' : 'DIRECT'; }",
+            "http://a/",
+            "a",
+            "127.0.0.1"
+        )
+        .is_err());
+    }
+}
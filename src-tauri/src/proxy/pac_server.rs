@@ -0,0 +1,140 @@
+//! 本机 PAC 服务
+//!
+//! 在 127.0.0.1 上监听，只响应 `GET`/`HEAD` 请求并返回当前脚本。
+//! 同一端口上更新脚本不需要重启监听，修改端口时关闭旧的监听再重新绑定。
+
+use std::sync::{Arc, OnceLock, RwLock};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{oneshot, Mutex};
+
+/// 请求头的最大长度
+const MAX_REQUEST_SIZE: usize = 8 * 1024;
+
+struct RunningServer {
+    port: u16,
+    script: Arc<RwLock<String>>,
+    shutdown: oneshot::Sender<()>,
+}
+
+fn server_state() -> &'static Mutex<Option<RunningServer>> {
+    static INSTANCE: OnceLock<Mutex<Option<RunningServer>>> = OnceLock::new();
+    INSTANCE.get_or_init(|| Mutex::new(None))
+}
+
+/// 启动服务（已在同一端口运行时只更新脚本）
+pub async fn start(port: u16, script: String) -> Result<(), String> {
+    let mut state = server_state().lock().await;
+    if let Some(server) = state.as_ref().filter(|s| s.port == port) {
+        if let Ok(mut current) = server.script.write() {
+            *current = script;
+        }
+        return Ok(());
+    }
+
+    if let Some(old) = state.take() {
+        let _ = old.shutdown.send(());
+    }
+
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .map_err(|e| format!("PAC 服务监听端口 {} 失败: {}", port, e))?;
+    let script = Arc::new(RwLock::new(script));
+    let (shutdown, mut shutdown_rx) = oneshot::channel();
+
+    let served = script.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = &mut shutdown_rx => break,
+                accepted = listener.accept() => {
+                    let Ok((stream, _)) = accepted else { continue };
+                    let script = served.read().map(|s| s.clone()).unwrap_or_default();
+                    tokio::spawn(async move {
+                        let _ = handle_connection(stream, &script).await;
+                    });
+                }
+            }
+        }
+    });
+
+    *state = Some(RunningServer { port, script, shutdown });
+    Ok(())
+}
+
+/// 停止服务
+pub async fn stop() {
+    if let Some(server) = server_state().lock().await.take() {
+        let _ = server.shutdown.send(());
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, script: &str) -> std::io::Result<()> {
+    let mut request = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < MAX_REQUEST_SIZE {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        request.extend_from_slice(&buf[..n]);
+    }
+
+    let request = String::from_utf8_lossy(&request);
+    let response = build_response(request.lines().next().unwrap_or(""), script);
+    stream.write_all(&response).await?;
+    stream.shutdown().await
+}
+
+/// 根据请求行生成响应
+fn build_response(request_line: &str, script: &str) -> Vec<u8> {
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("");
+    let path = parts.next().unwrap_or("");
+    let path = path.split('?').next().unwrap_or("");
+
+    let (status, content_type, body) = match method {
+        "GET" | "HEAD" if path == "/proxy.pac" || path == "/" => {
+            ("200 OK", "application/x-ns-proxy-autoconfig", script)
+        }
+        "GET" | "HEAD" => ("404 Not Found", "text/plain; charset=utf-8", "Not Found"),
+        _ => ("405 Method Not Allowed", "text/plain; charset=utf-8", "Method Not Allowed"),
+    };
+
+    let mut response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    )
+    .into_bytes();
+    if method != "HEAD" {
+        response.extend_from_slice(body.as_bytes());
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_response() {
+        let script = "function FindProxyForURL(url, host) { return \"DIRECT\"; }";
+        let ok = String::from_utf8(build_response("GET /proxy.pac?v=1234 HTTP/1.1", script)).unwrap();
+        assert!(ok.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(ok.contains("Content-Type: application/x-ns-proxy-autoconfig\r\n"));
+        assert!(ok.ends_with(script));
+
+        let head = String::from_utf8(build_response("HEAD / HTTP/1.1", script)).unwrap();
+        assert!(head.ends_with("\r\n\r\n"));
+        assert!(head.contains(&format!("Content-Length: {}\r\n", script.len())));
+
+        assert!(String::from_utf8(build_response("GET /other HTTP/1.1", script))
+            .unwrap()
+            .starts_with("HTTP/1.1 404"));
+        assert!(String::from_utf8(build_response("POST /proxy.pac HTTP/1.1", script))
+            .unwrap()
+            .starts_with("HTTP/1.1 405"));
+    }
+}
@@ -0,0 +1,285 @@
+//! 代理连通性测试
+//!
+//! 应用代理设置之前先经由代理访问测试地址：记录 TCP 连接耗时、
+//! CONNECT（HTTPS）或 GET（HTTP）的响应状态、是否需要认证（Basic/NTLM 等），
+//! 最后再通过代理请求出口 IP 查询地址，确认代理确实可用。
+
+use super::ProxyServers;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+pub const DEFAULT_TARGET_URL: &str = "https://www.gstatic.com/generate_204";
+pub const DEFAULT_EGRESS_URL: &str = "https://api.ipify.org";
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+/// 响应头的最大长度
+const MAX_HEAD_SIZE: usize = 16 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyKind {
+    Http,
+    Socks,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyTestResult {
+    /// 实际测试的代理地址
+    pub proxy: String,
+    pub kind: Option<ProxyKind>,
+    pub target_url: String,
+    /// TCP 连接耗时（毫秒）
+    pub connect_ms: Option<u64>,
+    /// CONNECT 或 HTTP 请求的响应状态
+    pub status: Option<u16>,
+    pub auth_required: bool,
+    /// 代理要求的认证方式，如 Basic、NTLM、Negotiate
+    pub auth_schemes: Vec<String>,
+    pub egress_ip: Option<String>,
+    pub error: Option<String>,
+}
+
+/// 按目标地址的协议选择要测试的代理
+pub fn select_proxy(servers: &ProxyServers, target_scheme: &str) -> Option<(ProxyKind, String)> {
+    let http = match target_scheme {
+        "https" => servers.https.as_ref().or(servers.all.as_ref()),
+        _ => servers.http.as_ref().or(servers.all.as_ref()),
+    };
+    let (kind, server) = match (http, servers.socks.as_ref()) {
+        (Some(server), _) => (ProxyKind::Http, server),
+        (None, Some(server)) => (ProxyKind::Socks, server),
+        (None, None) => return None,
+    };
+    // WinINet 允许写成 http://host:port
+    let address = server.split_once("://").map_or(server.as_str(), |(_, rest)| rest);
+    Some((kind, address.trim_end_matches('/').to_string()))
+}
+
+/// 经由代理测试目标地址
+pub async fn run(servers: &ProxyServers, target_url: &str, egress_url: &str) -> Result<ProxyTestResult, String> {
+    let target = reqwest::Url::parse(target_url).map_err(|e| format!("无效的测试地址 {}: {}", target_url, e))?;
+    let host = target
+        .host_str()
+        .ok_or_else(|| format!("测试地址中缺少主机名: {}", target_url))?
+        .to_string();
+    let port = target.port_or_known_default().unwrap_or(80);
+    let (kind, proxy) = select_proxy(servers, target.scheme()).ok_or_else(|| "未配置代理服务器".to_string())?;
+
+    let mut result = ProxyTestResult {
+        proxy: proxy.clone(),
+        kind: Some(kind),
+        target_url: target_url.to_string(),
+        ..Default::default()
+    };
+
+    let started = Instant::now();
+    let mut stream = match tokio::time::timeout(TIMEOUT, TcpStream::connect(&proxy)).await {
+        Ok(Ok(stream)) => stream,
+        Ok(Err(e)) => return Ok(result.failed(format!("连接代理失败: {}", e))),
+        Err(_) => return Ok(result.failed("连接代理超时".to_string())),
+    };
+    result.connect_ms = Some(started.elapsed().as_millis() as u64);
+
+    let handshake = match kind {
+        ProxyKind::Http => {
+            let request = if target.scheme() == "https" {
+                format!("CONNECT {0}:{1} HTTP/1.1\r\nHost: {0}:{1}\r\n\r\n", host, port)
+            } else {
+                format!("GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n", target, host)
+            };
+            tokio::time::timeout(TIMEOUT, http_handshake(&mut stream, &request)).await
+        }
+        ProxyKind::Socks => tokio::time::timeout(TIMEOUT, socks5_handshake(&mut stream, &host, port)).await,
+    };
+    match handshake {
+        Ok(Ok((status, schemes))) => {
+            result.status = status;
+            result.auth_required = status == Some(407) || !schemes.is_empty();
+            result.auth_schemes = schemes;
+        }
+        Ok(Err(e)) => return Ok(result.failed(e)),
+        Err(_) => return Ok(result.failed("等待代理响应超时".to_string())),
+    }
+    drop(stream);
+
+    if result.auth_required {
+        return Ok(result.failed("代理需要认证".to_string()));
+    }
+    if let Some(status) = result.status.filter(|s| !(200..400).contains(s)) {
+        return Ok(result.failed(format!("代理返回 HTTP {}", status)));
+    }
+
+    match fetch_egress_ip(kind, &proxy, egress_url).await {
+        Ok(ip) => result.egress_ip = Some(ip),
+        Err(e) => result.error = Some(e),
+    }
+    Ok(result)
+}
+
+impl ProxyTestResult {
+    fn failed(mut self, error: String) -> Self {
+        self.error = Some(error);
+        self
+    }
+}
+
+/// 发送请求并读取响应头，返回状态码与 `Proxy-Authenticate` 中的认证方式
+async fn http_handshake(stream: &mut TcpStream, request: &str) -> Result<(Option<u16>, Vec<String>), String> {
+    stream
+        .write_all(request.as_bytes())
+        .await
+        .map_err(|e| format!("发送请求失败: {}", e))?;
+
+    let mut head = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") && head.len() < MAX_HEAD_SIZE {
+        let n = stream.read(&mut buf).await.map_err(|e| format!("读取代理响应失败: {}", e))?;
+        if n == 0 {
+            break;
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+    if head.is_empty() {
+        return Err("代理关闭了连接".to_string());
+    }
+    parse_response_head(&String::from_utf8_lossy(&head))
+}
+
+/// 解析响应头
+fn parse_response_head(head: &str) -> Result<(Option<u16>, Vec<String>), String> {
+    let mut lines = head.lines();
+    let status_line = lines.next().unwrap_or("");
+    let status = status_line
+        .strip_prefix("HTTP/")
+        .and_then(|rest| rest.split_whitespace().nth(1))
+        .and_then(|code| code.parse::<u16>().ok())
+        .ok_or_else(|| format!("无法识别的代理响应: {}", status_line.trim()))?;
+
+    let mut schemes: Vec<String> = Vec::new();
+    for line in lines.take_while(|l| !l.trim().is_empty()) {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        if !name.trim().eq_ignore_ascii_case("proxy-authenticate") {
+            continue;
+        }
+        if let Some(scheme) = value.split_whitespace().next() {
+            let scheme = scheme.trim_end_matches(',').to_string();
+            if !schemes.iter().any(|s| s.eq_ignore_ascii_case(&scheme)) {
+                schemes.push(scheme);
+            }
+        }
+    }
+    Ok((Some(status), schemes))
+}
+
+/// SOCKS5 握手（不带认证）；代理只接受用户名密码认证时返回 `["Basic"]`
+async fn socks5_handshake(stream: &mut TcpStream, host: &str, port: u16) -> Result<(Option<u16>, Vec<String>), String> {
+    let io_err = |e: std::io::Error| format!("SOCKS 握手失败: {}", e);
+
+    stream.write_all(&[0x05, 0x02, 0x00, 0x02]).await.map_err(io_err)?;
+    let mut reply = [0u8; 2];
+    stream.read_exact(&mut reply).await.map_err(io_err)?;
+    match reply {
+        [0x05, 0x00] => {}
+        [0x05, 0x02] => return Ok((None, vec!["Basic".to_string()])),
+        _ => return Err("代理不是 SOCKS5 服务或不支持可用的认证方式".to_string()),
+    }
+
+    let host = host.as_bytes();
+    if host.len() > 255 {
+        return Err("主机名过长".to_string());
+    }
+    let mut request = vec![0x05, 0x01, 0x00, 0x03, host.len() as u8];
+    request.extend_from_slice(host);
+    request.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&request).await.map_err(io_err)?;
+
+    let mut reply = [0u8; 2];
+    stream.read_exact(&mut reply).await.map_err(io_err)?;
+    if reply[1] != 0x00 {
+        return Err(format!("SOCKS 代理拒绝连接目标地址（错误码 {}）", reply[1]));
+    }
+    Ok((None, Vec::new()))
+}
+
+/// 通过代理请求出口 IP 查询地址
+async fn fetch_egress_ip(kind: ProxyKind, proxy: &str, egress_url: &str) -> Result<String, String> {
+    let proxy_url = match kind {
+        ProxyKind::Http => format!("http://{}", proxy),
+        ProxyKind::Socks => format!("socks5h://{}", proxy),
+    };
+    let client = reqwest::Client::builder()
+        .proxy(reqwest::Proxy::all(&proxy_url).map_err(|e| format!("无效的代理地址: {}", e))?)
+        .timeout(TIMEOUT)
+        .build()
+        .map_err(|e| format!("创建HTTP客户端失败: {}", e))?;
+    let response = client
+        .get(egress_url)
+        .send()
+        .await
+        .map_err(|e| format!("获取出口 IP 失败: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("获取出口 IP 失败: HTTP {}", response.status()));
+    }
+    let body = response.text().await.map_err(|e| format!("获取出口 IP 失败: {}", e))?;
+    parse_egress_ip(&body).ok_or_else(|| "无法识别出口 IP 查询结果".to_string())
+}
+
+/// 支持纯文本（如 ipify）与带 `ip`/`origin` 字段的 JSON（如 httpbin）
+fn parse_egress_ip(body: &str) -> Option<String> {
+    let body = body.trim();
+    let candidate = match serde_json::from_str::<serde_json::Value>(body) {
+        Ok(serde_json::Value::Object(map)) => map
+            .get("ip")
+            .or_else(|| map.get("origin"))
+            .and_then(|v| v.as_str())?
+            .split(',')
+            .next()?
+            .trim()
+            .to_string(),
+        _ => body.to_string(),
+    };
+    candidate.parse::<std::net::IpAddr>().ok().map(|ip| ip.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_response_head() {
+        let head = "HTTP/1.1 407 Proxy Authentication Required\r\n\
+                    Proxy-Authenticate: NTLM\r\n\
+                    proxy-authenticate: Basic realm=\"corp\"\r\n\
+                    Proxy-Authenticate: Negotiate\r\n\
+                    Proxy-Authenticate: basic realm=\"other\"\r\n\r\n";
+        assert_eq!(
+            parse_response_head(head).unwrap(),
+            (Some(407), vec!["NTLM".to_string(), "Basic".to_string(), "Negotiate".to_string()])
+        );
+        assert_eq!(
+            parse_response_head("HTTP/1.0 200 Connection established\r\n\r\n").unwrap(),
+            (Some(200), Vec::new())
+        );
+        assert!(parse_response_head("SSH-2.0-OpenSSH\r\n").is_err());
+    }
+
+    #[test]
+    fn test_select_proxy_and_parse_egress_ip() {
+        let servers = ProxyServers::parse("http=http://proxy:80;https=secure:443;socks=127.0.0.1:1080");
+        assert_eq!(select_proxy(&servers, "http"), Some((ProxyKind::Http, "proxy:80".to_string())));
+        assert_eq!(select_proxy(&servers, "https"), Some((ProxyKind::Http, "secure:443".to_string())));
+        let socks_only = ProxyServers::parse("socks=127.0.0.1:1080");
+        assert_eq!(select_proxy(&socks_only, "https"), Some((ProxyKind::Socks, "127.0.0.1:1080".to_string())));
+        assert_eq!(select_proxy(&ProxyServers::default(), "http"), None);
+
+        assert_eq!(parse_egress_ip("203.0.113.7\n").as_deref(), Some("203.0.113.7"));
+        assert_eq!(parse_egress_ip(r#"{"origin": "203.0.113.7, 10.0.0.1"}"#).as_deref(), Some("203.0.113.7"));
+        assert_eq!(parse_egress_ip(r#"{"ip":"2001:db8::1"}"#).as_deref(), Some("2001:db8::1"));
+        assert_eq!(parse_egress_ip("<html>blocked</html>"), None);
+    }
+}
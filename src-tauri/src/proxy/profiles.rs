@@ -0,0 +1,151 @@
+//! 代理配置方案
+//!
+//! 与场景独立保存的命名代理设置，可在界面或托盘菜单中一键切换。
+//! 内置的 `direct`（直连）方案不保存在文件中，也不能修改或删除。
+
+use super::ProxyConfig;
+use serde::{Deserialize, Serialize};
+
+pub const DIRECT_PROFILE: &str = "direct";
+
+/// 名称的最大长度（托盘菜单中显示）
+const MAX_NAME_LEN: usize = 64;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyProfile {
+    pub name: String,
+    /// 内置方案，只在列表中返回
+    #[serde(default, skip_deserializing)]
+    pub builtin: bool,
+    pub config: ProxyConfig,
+    /// 系统凭据库中的认证信息 id
+    #[serde(default)]
+    pub credential: Option<String>,
+}
+
+impl ProxyProfile {
+    pub fn direct() -> Self {
+        Self {
+            name: DIRECT_PROFILE.to_string(),
+            builtin: true,
+            config: ProxyConfig::default(),
+            credential: None,
+        }
+    }
+
+    /// 应用后的效果是否与系统当前设置相同。
+    /// 关闭代理时系统会保留原来的服务器与绕过列表，此时不比较这两项
+    pub fn is_active(&self, current: &ProxyConfig) -> bool {
+        let config = &self.config;
+        config.enabled == current.enabled
+            && config.auto_detect == current.auto_detect
+            && config.auto_config_url.trim() == current.auto_config_url.trim()
+            && (!config.enabled
+                || (config.servers.to_registry_string() == current.servers.to_registry_string()
+                    && config.bypass == current.bypass))
+    }
+}
+
+/// 内置方案在前，其后是保存的方案
+pub fn with_builtin(saved: Vec<ProxyProfile>) -> Vec<ProxyProfile> {
+    std::iter::once(ProxyProfile::direct()).chain(saved).collect()
+}
+
+pub fn validate_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("方案名称不能为空".to_string());
+    }
+    if name.eq_ignore_ascii_case(DIRECT_PROFILE) {
+        return Err(format!("{} 是内置方案，不能修改", DIRECT_PROFILE));
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return Err(format!("方案名称不能超过 {} 个字符", MAX_NAME_LEN));
+    }
+    Ok(name.to_string())
+}
+
+/// 新建或替换同名方案
+pub fn upsert(profiles: &mut Vec<ProxyProfile>, profile: ProxyProfile) {
+    match profiles.iter_mut().find(|p| p.name == profile.name) {
+        Some(existing) => *existing = profile,
+        None => profiles.push(profile),
+    }
+}
+
+pub fn remove(profiles: &mut Vec<ProxyProfile>, name: &str) -> Result<(), String> {
+    if name.eq_ignore_ascii_case(DIRECT_PROFILE) {
+        return Err(format!("{} 是内置方案，不能删除", DIRECT_PROFILE));
+    }
+    let before = profiles.len();
+    profiles.retain(|p| p.name != name);
+    if profiles.len() == before {
+        return Err(format!("代理方案 {} 不存在", name));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proxy::ProxyServers;
+
+    fn profile(name: &str, server: &str) -> ProxyProfile {
+        ProxyProfile {
+            name: name.to_string(),
+            builtin: false,
+            config: ProxyConfig {
+                enabled: true,
+                servers: ProxyServers::parse(server),
+                bypass: vec!["<local>".to_string()],
+                ..Default::default()
+            },
+            credential: None,
+        }
+    }
+
+    #[test]
+    fn test_edit_profiles() {
+        let mut profiles = Vec::new();
+        upsert(&mut profiles, profile("clash", "127.0.0.1:7890"));
+        upsert(&mut profiles, profile("corp", "10.0.0.1:3128"));
+        upsert(&mut profiles, profile("clash", "127.0.0.1:7897"));
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].config.servers.all.as_deref(), Some("127.0.0.1:7897"));
+
+        assert!(remove(&mut profiles, "Direct").is_err());
+        assert!(remove(&mut profiles, "missing").is_err());
+        remove(&mut profiles, "corp").unwrap();
+
+        let all = with_builtin(profiles);
+        assert_eq!(all.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(), vec!["direct", "clash"]);
+        assert!(all[0].builtin);
+
+        assert_eq!(validate_name("  home ").unwrap(), "home");
+        assert!(validate_name(" ").is_err());
+        assert!(validate_name("DIRECT").is_err());
+        assert!(validate_name(&"x".repeat(65)).is_err());
+    }
+
+    #[test]
+    fn test_active_profile() {
+        let clash = profile("clash", "127.0.0.1:7890");
+        assert!(clash.is_active(&clash.config));
+        assert!(!ProxyProfile::direct().is_active(&clash.config));
+
+        // 关闭代理后系统仍保留服务器，直连方案应视为生效
+        let off = ProxyConfig {
+            enabled: false,
+            ..clash.config.clone()
+        };
+        assert!(ProxyProfile::direct().is_active(&off));
+        assert!(!clash.is_active(&off));
+
+        let pac = ProxyConfig {
+            auto_config_url: "http://127.0.0.1:18086/proxy.pac".to_string(),
+            ..ProxyConfig::default()
+        };
+        assert!(!ProxyProfile::direct().is_active(&pac));
+    }
+}
@@ -0,0 +1,179 @@
+//! 代理服务器地址
+//!
+//! WinINet 的 `ProxyServer` 有两种写法：不带协议前缀的 `host:port` 表示所有协议共用，
+//! 或者 `http=host:port;https=host:port;ftp=host:port;socks=host:port` 按协议分别指定
+//! （分隔符也可以是空格）。这里把它解析为按协议区分的结构，保存时再序列化回去。
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyServers {
+    /// 所有协议共用的代理（不带协议前缀的写法）
+    pub all: Option<String>,
+    pub http: Option<String>,
+    pub https: Option<String>,
+    pub ftp: Option<String>,
+    pub socks: Option<String>,
+}
+
+impl ProxyServers {
+    /// 解析注册表中的 `ProxyServer` 值，无法识别的协议会被忽略
+    pub fn parse(value: &str) -> Self {
+        let mut servers = Self::default();
+        for item in value.split(|c: char| c == ';' || c.is_whitespace()) {
+            let item = item.trim();
+            if item.is_empty() {
+                continue;
+            }
+            let Some((scheme, server)) = item.split_once('=') else {
+                servers.all = Some(item.to_string());
+                continue;
+            };
+            let server = server.trim();
+            if server.is_empty() {
+                continue;
+            }
+            let slot = match scheme.trim().to_lowercase().as_str() {
+                "http" => &mut servers.http,
+                "https" => &mut servers.https,
+                "ftp" => &mut servers.ftp,
+                "socks" => &mut servers.socks,
+                _ => continue,
+            };
+            *slot = Some(server.to_string());
+        }
+        servers
+    }
+
+    /// 序列化为 `ProxyServer` 的值。
+    /// 只有共用代理时写成 `host:port`；同时指定了某些协议时，
+    /// 未单独指定的 HTTP/HTTPS/FTP 使用共用代理（SOCKS 不会继承共用代理，与 WinINet 一致）
+    pub fn to_registry_string(&self) -> String {
+        let all = self.all.as_deref().filter(|s| !s.is_empty());
+        let per_protocol = [
+            ("http", self.http.as_deref(), true),
+            ("https", self.https.as_deref(), true),
+            ("ftp", self.ftp.as_deref(), true),
+            ("socks", self.socks.as_deref(), false),
+        ];
+
+        if per_protocol.iter().all(|(_, server, _)| server.is_none_or(str::is_empty)) {
+            return all.unwrap_or_default().to_string();
+        }
+
+        per_protocol
+            .iter()
+            .filter_map(|(scheme, server, inherits)| {
+                let server = server
+                    .filter(|s| !s.is_empty())
+                    .or(if *inherits { all } else { None })?;
+                Some(format!("{}={}", scheme, server))
+            })
+            .collect::<Vec<_>>()
+            .join(";")
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.to_registry_string().is_empty()
+    }
+
+    /// 去掉首尾空白与空值，并校验每个地址
+    pub fn normalized(&self) -> Result<Self, String> {
+        let normalize = |value: &Option<String>| -> Result<Option<String>, String> {
+            match value.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+                Some(server) => validate_server(server).map(|_| Some(server.to_string())),
+                None => Ok(None),
+            }
+        };
+        Ok(Self {
+            all: normalize(&self.all)?,
+            http: normalize(&self.http)?,
+            https: normalize(&self.https)?,
+            ftp: normalize(&self.ftp)?,
+            socks: normalize(&self.socks)?,
+        })
+    }
+}
+
+/// 校验 `host:port`（允许带 `http://` 等前缀，IPv6 地址需用方括号）
+fn validate_server(server: &str) -> Result<(), String> {
+    let address = server.split_once("://").map_or(server, |(_, rest)| rest);
+    let address = address.trim_end_matches('/');
+    let invalid = || format!("无效的代理服务器地址: {}", server);
+
+    let (host, port) = address.rsplit_once(':').ok_or_else(invalid)?;
+    let host_valid = if let Some(ip) = host.strip_prefix('[').and_then(|h| h.strip_suffix(']')) {
+        ip.parse::<std::net::Ipv6Addr>().is_ok()
+    } else {
+        !host.is_empty()
+            && host
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+    };
+    let port_valid = port.parse::<u16>().is_ok_and(|p| p != 0);
+    if host_valid && port_valid {
+        Ok(())
+    } else {
+        Err(invalid())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_proxy_server_string() {
+        assert_eq!(
+            ProxyServers::parse("127.0.0.1:7890"),
+            ProxyServers {
+                all: Some("127.0.0.1:7890".to_string()),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            ProxyServers::parse("HTTP=proxy:80; https=proxy:443 socks=127.0.0.1:1080;gopher=old:70;ftp="),
+            ProxyServers {
+                http: Some("proxy:80".to_string()),
+                https: Some("proxy:443".to_string()),
+                socks: Some("127.0.0.1:1080".to_string()),
+                ..Default::default()
+            }
+        );
+        assert!(ProxyServers::parse(" ; ").is_empty());
+    }
+
+    #[test]
+    fn test_serialize_and_validate_proxy_servers() {
+        let same = ProxyServers::parse("proxy:8080");
+        assert_eq!(same.to_registry_string(), "proxy:8080");
+
+        let mixed = ProxyServers {
+            all: Some("proxy:8080".to_string()),
+            https: Some("secure:8443".to_string()),
+            socks: Some("127.0.0.1:1080".to_string()),
+            ..Default::default()
+        };
+        let value = mixed.to_registry_string();
+        assert_eq!(value, "http=proxy:8080;https=secure:8443;ftp=proxy:8080;socks=127.0.0.1:1080");
+        assert_eq!(ProxyServers::parse(&value).to_registry_string(), value);
+
+        let input = ProxyServers {
+            http: Some(" http://[::1]:3128 ".to_string()),
+            ftp: Some(String::new()),
+            ..Default::default()
+        };
+        let normalized = input.normalized().unwrap();
+        assert_eq!(normalized.http.as_deref(), Some("http://[::1]:3128"));
+        assert_eq!(normalized.ftp, None);
+
+        for bad in ["proxy", "proxy:0", "proxy:99999", "bad host:80", "[zz]:80"] {
+            let servers = ProxyServers {
+                all: Some(bad.to_string()),
+                ..Default::default()
+            };
+            assert!(servers.normalized().is_err(), "{}", bad);
+        }
+    }
+}
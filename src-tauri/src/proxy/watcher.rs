@@ -0,0 +1,274 @@
+//! 监听系统代理设置的变化
+//!
+//! Clash 等工具会直接改写 `Internet Settings`。后台线程等待注册表变化通知
+//! （`RegNotifyChangeKeyValue`，不可用时退回定时轮询），重新读取设置并与上一次比较，
+//! 有变化时发送 `proxy-changed` 事件。本应用自己写入的设置标记为 `app`，其余为 `external`。
+
+use super::ProxyConfig;
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+
+pub const EVENT: &str = "proxy-changed";
+
+/// 轮询间隔；使用注册表通知时也按此间隔重新核对一次，避免漏掉通知间隙中的修改
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// 一次修改通常会连续写入多个值，收到通知后稍等再读取
+#[cfg(target_os = "windows")]
+const SETTLE_DELAY: Duration = Duration::from_millis(200);
+
+/// 本应用写入后，在这段时间内读到相同的设置视为自己的修改
+const OWN_WRITE_WINDOW: Duration = Duration::from_secs(10);
+
+/// 等待“设置可能已变化”的信号
+pub trait ChangeSignal: Send {
+    fn wait(&mut self);
+}
+
+impl ChangeSignal for Box<dyn ChangeSignal> {
+    fn wait(&mut self) {
+        (**self).wait();
+    }
+}
+
+/// 定时轮询
+pub struct PollingSignal {
+    interval: Duration,
+}
+
+impl PollingSignal {
+    pub fn new(interval: Duration) -> Self {
+        Self { interval }
+    }
+}
+
+impl ChangeSignal for PollingSignal {
+    fn wait(&mut self) {
+        std::thread::sleep(self.interval);
+    }
+}
+
+/// 注册表变化通知（超时后也返回，相当于低频轮询）
+#[cfg(target_os = "windows")]
+pub struct RegistrySignal {
+    key: winreg::RegKey,
+    event: winapi::um::winnt::HANDLE,
+}
+
+// 事件句柄只在监听线程中使用
+#[cfg(target_os = "windows")]
+unsafe impl Send for RegistrySignal {}
+
+#[cfg(target_os = "windows")]
+impl RegistrySignal {
+    pub fn open(path: &str) -> Result<Self, String> {
+        use winreg::enums::{HKEY_CURRENT_USER, KEY_NOTIFY, KEY_READ};
+
+        let key = winreg::RegKey::predef(HKEY_CURRENT_USER)
+            .open_subkey_with_flags(path, KEY_READ | KEY_NOTIFY)
+            .map_err(|e| format!("打开注册表失败: {}", e))?;
+        let event = unsafe { winapi::um::synchapi::CreateEventW(std::ptr::null_mut(), 0, 0, std::ptr::null()) };
+        if event.is_null() {
+            return Err(format!("创建事件失败: {}", std::io::Error::last_os_error()));
+        }
+        Ok(Self { key, event })
+    }
+}
+
+#[cfg(target_os = "windows")]
+impl ChangeSignal for RegistrySignal {
+    fn wait(&mut self) {
+        use winapi::um::synchapi::WaitForSingleObject;
+        use winapi::um::winnt::{REG_NOTIFY_CHANGE_LAST_SET, REG_NOTIFY_CHANGE_NAME};
+        use winapi::um::winreg::RegNotifyChangeKeyValue;
+
+        // 包含子键，`Connections` 下的二进制设置也会触发
+        let status = unsafe {
+            RegNotifyChangeKeyValue(
+                self.key.raw_handle(),
+                1,
+                REG_NOTIFY_CHANGE_LAST_SET | REG_NOTIFY_CHANGE_NAME,
+                self.event,
+                1,
+            )
+        };
+        if status != 0 {
+            std::thread::sleep(POLL_INTERVAL);
+            return;
+        }
+        let timeout = POLL_INTERVAL.as_millis() as u32 * 10;
+        unsafe { WaitForSingleObject(self.event, timeout) };
+        std::thread::sleep(SETTLE_DELAY);
+    }
+}
+
+#[cfg(target_os = "windows")]
+impl Drop for RegistrySignal {
+    fn drop(&mut self) {
+        unsafe { winapi::um::handleapi::CloseHandle(self.event) };
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyChangeSource {
+    /// 本应用（`set_proxy`、PAC 服务等）写入
+    App,
+    /// 其他程序写入
+    External,
+}
+
+/// `proxy-changed` 事件内容
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyChangedEvent {
+    pub previous: ProxyConfig,
+    pub current: ProxyConfig,
+    pub source: ProxyChangeSource,
+}
+
+/// 本应用最近一次写入后的设置
+fn own_write() -> &'static Mutex<Option<(ProxyConfig, Instant)>> {
+    static INSTANCE: Mutex<Option<(ProxyConfig, Instant)>> = Mutex::new(None);
+    &INSTANCE
+}
+
+/// 记录本应用写入后的设置，供监听器区分修改来源
+pub fn record_own_write(config: ProxyConfig) {
+    if let Ok(mut own) = own_write().lock() {
+        *own = Some((config, Instant::now()));
+    }
+}
+
+/// 读到的设置是否与最近一次自己写入的相同（匹配后清除记录）
+fn take_own_write(config: &ProxyConfig) -> bool {
+    let Ok(mut own) = own_write().lock() else {
+        return false;
+    };
+    let matched = own
+        .as_ref()
+        .is_some_and(|(written, at)| written == config && at.elapsed() < OWN_WRITE_WINDOW);
+    if matched {
+        *own = None;
+    }
+    matched
+}
+
+pub struct Watcher<S, R> {
+    signal: S,
+    read: R,
+    last: ProxyConfig,
+}
+
+impl<S, R> Watcher<S, R>
+where
+    S: ChangeSignal,
+    R: FnMut() -> Result<ProxyConfig, String>,
+{
+    pub fn new(signal: S, mut read: R) -> Result<Self, String> {
+        let last = read()?;
+        Ok(Self { signal, read, last })
+    }
+
+    /// 等待下一次信号，设置有变化时返回事件
+    pub fn next(&mut self) -> Result<Option<ProxyChangedEvent>, String> {
+        self.signal.wait();
+        let current = (self.read)()?;
+        if current == self.last {
+            return Ok(None);
+        }
+        let source = if take_own_write(&current) {
+            ProxyChangeSource::App
+        } else {
+            ProxyChangeSource::External
+        };
+        let previous = std::mem::replace(&mut self.last, current.clone());
+        Ok(Some(ProxyChangedEvent { previous, current, source }))
+    }
+}
+
+/// 在后台线程中监听并向前端发送事件
+pub fn start<R>(app: tauri::AppHandle, read: R)
+where
+    R: FnMut() -> Result<ProxyConfig, String> + Send + 'static,
+{
+    std::thread::spawn(move || {
+        #[cfg(target_os = "windows")]
+        let signal: Box<dyn ChangeSignal> = match RegistrySignal::open(super::INTERNET_SETTINGS_KEY) {
+            Ok(signal) => Box::new(signal),
+            Err(e) => {
+                eprintln!("注册表变化通知不可用，改为轮询: {}", e);
+                Box::new(PollingSignal::new(POLL_INTERVAL))
+            }
+        };
+        #[cfg(not(target_os = "windows"))]
+        let signal: Box<dyn ChangeSignal> = Box::new(PollingSignal::new(POLL_INTERVAL));
+
+        let mut watcher = match Watcher::new(signal, read) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                eprintln!("监听代理设置失败: {}", e);
+                return;
+            }
+        };
+        loop {
+            match watcher.next() {
+                Ok(Some(event)) => {
+                    let _ = app.emit(EVENT, &event);
+                }
+                Ok(None) => {}
+                Err(e) => eprintln!("读取代理设置失败: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    struct ManualSignal;
+
+    impl ChangeSignal for ManualSignal {
+        fn wait(&mut self) {}
+    }
+
+    fn config(server: &str) -> ProxyConfig {
+        ProxyConfig {
+            enabled: !server.is_empty(),
+            servers: crate::proxy::ProxyServers::parse(server),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_watcher_reports_changes_and_source() {
+        let mut reads: VecDeque<Result<ProxyConfig, String>> = VecDeque::from(vec![
+            Ok(config("")),
+            Ok(config("")),
+            Ok(config("127.0.0.1:7890")),
+            Err("读取失败".to_string()),
+            Ok(config("10.9.8.7:3128")),
+            Ok(config("10.9.8.7:3128")),
+        ]);
+        let mut watcher = Watcher::new(ManualSignal, move || reads.pop_front().unwrap()).unwrap();
+
+        assert_eq!(watcher.next().unwrap(), None);
+
+        let event = watcher.next().unwrap().unwrap();
+        assert_eq!(event.previous, config(""));
+        assert_eq!(event.current, config("127.0.0.1:7890"));
+        assert_eq!(event.source, ProxyChangeSource::External);
+
+        assert!(watcher.next().is_err());
+
+        record_own_write(config("10.9.8.7:3128"));
+        let event = watcher.next().unwrap().unwrap();
+        assert_eq!(event.previous, config("127.0.0.1:7890"));
+        assert_eq!(event.source, ProxyChangeSource::App);
+        assert_eq!(watcher.next().unwrap(), None);
+    }
+}
@@ -4,7 +4,20 @@ use std::fs;
 use std::path::PathBuf;
 use tauri::Manager;
 
-#[derive(Debug, Serialize, Deserialize)]
+use crate::storage;
+
+mod bundle;
+mod linux_formats;
+mod search;
+mod sources;
+mod watcher;
+
+pub use bundle::TrustedPublisher;
+pub use linux_formats::{LinuxNetFile, LinuxNetFormat};
+pub use search::SceneQuery;
+pub use sources::{SceneSource, SceneSourceKind, SceneSourceStatus};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Scene {
     pub name: String,
     pub network_configs: HashMap<String, NetworkConfig>,
@@ -12,6 +25,28 @@ pub struct Scene {
     pub proxy_config: Option<ProxyConfig>,
     #[serde(default)]
     pub tray_color: Option<String>, // 托盘图标颜色（十六进制，如 "#3366FF"）
+    /// 场景来源名称（None 表示个人目录中的场景，不写入场景文件）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// 站点/位置（如 "上海办公室"）
+    #[serde(default)]
+    pub site: Option<String>,
+    /// 图标名称或 emoji，由前端解释
+    #[serde(default)]
+    pub icon: Option<String>,
+    /// 以下时间均为 Unix 毫秒时间戳
+    #[serde(default)]
+    pub created_at: Option<u64>,
+    #[serde(default)]
+    pub modified_at: Option<u64>,
+    #[serde(default)]
+    pub last_applied_at: Option<u64>,
+    #[serde(default)]
+    pub times_applied: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -30,6 +65,29 @@ pub struct ProxyConfig {
     pub bypass: Vec<String>,
 }
 
+/// 编辑场景时提交的内容，除网卡配置外未提供的字段保持不变
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct SceneUpdate {
+    pub network_configs: HashMap<String, NetworkConfig>,
+    pub hosts_content: Option<String>,
+    pub proxy_config: Option<ProxyConfig>,
+    pub tray_color: Option<String>,
+    /// 元数据中空字符串表示清除
+    pub description: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub site: Option<String>,
+    pub icon: Option<String>,
+}
+
+/// 当前时间（Unix 毫秒时间戳）
+pub(crate) fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
 fn get_scenes_dir() -> PathBuf {
     // legacy: scenes folder next to the executable
     let mut path = std::env::current_exe().unwrap_or_else(|_| PathBuf::from("."));
@@ -84,6 +142,14 @@ fn migrate_legacy_scenes_if_needed(legacy: &PathBuf, target: &PathBuf) -> Result
     Ok(())
 }
 
+/// 启动场景目录监听，外部修改场景文件时向前端推送事件
+pub fn start_scene_watcher(app: &tauri::AppHandle) -> Result<(), String> {
+    let dir = ensure_scenes_dir(app)?;
+    let watcher = watcher::start(app.clone(), dir)?;
+    app.manage(watcher);
+    Ok(())
+}
+
 fn ensure_scenes_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
     let dir = get_scenes_dir_in_documents(app)?;
     fs::create_dir_all(&dir).map_err(|e| format!("创建场景目录失败: {}", e))?;
@@ -95,9 +161,9 @@ fn ensure_scenes_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
     Ok(dir)
 }
 
-#[tauri::command]
-pub async fn get_scenes(app: tauri::AppHandle) -> Result<Vec<Scene>, String> {
-    let scenes_dir = ensure_scenes_dir(&app)?;
+/// 读取个人场景目录中的场景
+fn read_personal_scenes(app: &tauri::AppHandle) -> Result<Vec<Scene>, String> {
+    let scenes_dir = ensure_scenes_dir(app)?;
     let mut scenes = Vec::new();
 
     let entries = fs::read_dir(&scenes_dir)
@@ -116,7 +182,8 @@ pub async fn get_scenes(app: tauri::AppHandle) -> Result<Vec<Scene>, String> {
         
         if path.extension().and_then(|s| s.to_str()) == Some("json") {
             if let Ok(content) = fs::read_to_string(&path) {
-                if let Ok(scene) = serde_json::from_str::<Scene>(&content) {
+                if let Ok(mut scene) = serde_json::from_str::<Scene>(&content) {
+                    scene.source = None;
                     scenes.push(scene);
                 }
             }
@@ -126,6 +193,22 @@ pub async fn get_scenes(app: tauri::AppHandle) -> Result<Vec<Scene>, String> {
     Ok(scenes)
 }
 
+/// 获取场景列表：个人场景 + 各只读来源中的场景（通过 `source` 区分）
+#[tauri::command]
+pub async fn get_scenes(app: tauri::AppHandle) -> Result<Vec<Scene>, String> {
+    let mut scenes = read_personal_scenes(&app)?;
+
+    // 来源不可用（如网络共享断开）时跳过，不影响个人场景
+    for source in load_scene_sources(&app)? {
+        match sources::read_source_scenes(&source) {
+            Ok(mut shared) => scenes.append(&mut shared),
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+
+    Ok(scenes)
+}
+
 #[tauri::command]
 pub async fn save_scene(app: tauri::AppHandle, scene_name: String) -> Result<(), String> {
     let scenes_dir = ensure_scenes_dir(&app)?;
@@ -150,22 +233,33 @@ pub async fn save_scene(app: tauri::AppHandle, scene_name: String) -> Result<(),
     let hosts_content = get_hosts().await.ok();
     
     // 获取当前代理配置
-    use crate::proxy::get_proxy;
+    use crate::proxy::{get_proxy, strip_proxy_credentials};
     let proxy_config = get_proxy().await.ok().map(|p| ProxyConfig {
         enabled: p.enabled,
-        server: p.server,
+        // 认证信息只保存在系统凭据库中
+        server: strip_proxy_credentials(&p.servers.to_registry_string()),
         bypass: p.bypass,
     });
     
+    let scene_file = scenes_dir.join(format!("{}.json", scene_name));
+
+    // 覆盖同名场景时保留托盘颜色、描述、标签与应用统计等元数据
+    let previous = fs::read_to_string(&scene_file)
+        .ok()
+        .and_then(|c| serde_json::from_str::<Scene>(&c).ok())
+        .unwrap_or_default();
+    let now = now_millis();
+
     let scene = Scene {
         name: scene_name.clone(),
         network_configs,
         hosts_content,
         proxy_config,
-        tray_color: None, // 保存场景时默认不设置托盘颜色
+        created_at: previous.created_at.or(Some(now)),
+        modified_at: Some(now),
+        ..previous
     };
     
-    let scene_file = scenes_dir.join(format!("{}.json", scene_name));
     let content = serde_json::to_string_pretty(&scene)
         .map_err(|e| format!("序列化场景失败: {}", e))?;
     
@@ -202,6 +296,7 @@ pub async fn save_backup(app: tauri::AppHandle) -> Result<(), String> {
         hosts_content: None,  // 不备份Hosts
         proxy_config: None,  // 不备份代理
         tray_color: None,
+        ..Default::default()
     };
     
     let backup_file = scenes_dir.join("_backup_before_scene.json");
@@ -314,24 +409,34 @@ pub async fn has_backup(app: tauri::AppHandle) -> Result<bool, String> {
 }
 
 #[tauri::command]
-pub async fn apply_scene(app: tauri::AppHandle, scene_name: String) -> Result<(), String> {
+pub async fn apply_scene(
+    app: tauri::AppHandle,
+    scene_name: String,
+    source: Option<String>,
+) -> Result<(), String> {
     // 在应用场景前，先保存当前配置为备份
     save_backup(app.clone()).await?;
     
-    let scenes_dir = ensure_scenes_dir(&app)?;
-    let scene_file = scenes_dir.join(format!("{}.json", scene_name));
-    
-    let content = fs::read_to_string(&scene_file)
-        .map_err(|e| format!("读取场景文件失败: {}", e))?;
-    
-    let scene: Scene = serde_json::from_str(&content)
-        .map_err(|e| format!("解析场景文件失败: {}", e))?;
+    let scene = match source {
+        // 只读来源中的场景
+        Some(source) => find_source_scene(&app, &source, &scene_name)?,
+        None => {
+            let scenes_dir = ensure_scenes_dir(&app)?;
+            let scene_file = scenes_dir.join(format!("{}.json", scene_name));
+
+            let content = fs::read_to_string(&scene_file)
+                .map_err(|e| format!("读取场景文件失败: {}", e))?;
+
+            serde_json::from_str::<Scene>(&content)
+                .map_err(|e| format!("解析场景文件失败: {}", e))?
+        }
+    };
     
     // 只应用网络配置（场景只管理网卡IP配置，不涉及Hosts和代理）
     // 串行执行所有网卡的配置，确保每个配置都成功应用
     use crate::network::{set_static_ip, set_dhcp};
     
-    for (adapter_name, config) in scene.network_configs {
+    for (adapter_name, config) in scene.network_configs.clone() {
         let adapter_name_clone = adapter_name.clone();
         if config.is_dhcp {
             set_dhcp(adapter_name).await
@@ -350,6 +455,13 @@ pub async fn apply_scene(app: tauri::AppHandle, scene_name: String) -> Result<()
     }
     
     // 不应用Hosts和代理配置，场景只管理网卡IP
+
+    // 记录应用时间与次数（只读来源中的场景不记录）
+    if scene.source.is_none() {
+        if let Err(e) = record_scene_applied(&app, &scene_name) {
+            eprintln!("记录场景应用统计失败: {}", e);
+        }
+    }
     
     // 注意：托盘颜色更新需要在调用 apply_scene 时传入 AppHandle
     // 这里暂时不处理，由前端调用 update_tray_icon_color
@@ -357,15 +469,42 @@ pub async fn apply_scene(app: tauri::AppHandle, scene_name: String) -> Result<()
     Ok(())
 }
 
+/// 更新场景的最近应用时间与应用次数（不修改 modified_at）
+fn record_scene_applied(app: &tauri::AppHandle, scene_name: &str) -> Result<(), String> {
+    let scenes_dir = ensure_scenes_dir(app)?;
+    let scene_file = scenes_dir.join(format!("{}.json", scene_name));
+
+    let content = fs::read_to_string(&scene_file)
+        .map_err(|e| format!("读取场景文件失败: {}", e))?;
+    let mut scene: Scene = serde_json::from_str(&content)
+        .map_err(|e| format!("解析场景文件失败: {}", e))?;
+
+    scene.last_applied_at = Some(now_millis());
+    scene.times_applied += 1;
+
+    let content = serde_json::to_string_pretty(&scene)
+        .map_err(|e| format!("序列化场景失败: {}", e))?;
+    fs::write(&scene_file, content)
+        .map_err(|e| format!("保存场景失败: {}", e))?;
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn update_scene(
     app: tauri::AppHandle,
     scene_name: String,
-    network_configs: HashMap<String, NetworkConfig>,
-    hosts_content: Option<String>,
-    proxy_config: Option<ProxyConfig>,
-    tray_color: Option<String>,
+    update: SceneUpdate,
 ) -> Result<(), String> {
+    let SceneUpdate {
+        network_configs,
+        hosts_content,
+        proxy_config,
+        tray_color,
+        description,
+        tags,
+        site,
+        icon,
+    } = update;
     let scenes_dir = ensure_scenes_dir(&app)?;
     let scene_file = scenes_dir.join(format!("{}.json", scene_name));
     
@@ -382,6 +521,8 @@ pub async fn update_scene(
             hosts_content: None,
             proxy_config: None,
             tray_color: None,
+            created_at: Some(now_millis()),
+            ..Default::default()
         }
     };
     
@@ -402,6 +543,25 @@ pub async fn update_scene(
     if let Some(color) = tray_color {
         scene.tray_color = Some(color);
     }
+
+    // 更新元数据（如果提供，空字符串表示清除）
+    if let Some(description) = description {
+        scene.description = Some(description).filter(|s| !s.trim().is_empty());
+    }
+    if let Some(tags) = tags {
+        scene.tags = tags
+            .into_iter()
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect();
+    }
+    if let Some(site) = site {
+        scene.site = Some(site).filter(|s| !s.trim().is_empty());
+    }
+    if let Some(icon) = icon {
+        scene.icon = Some(icon).filter(|s| !s.trim().is_empty());
+    }
+    scene.modified_at = Some(now_millis());
     
     // 保存场景
     let content = serde_json::to_string_pretty(&scene)
@@ -424,10 +584,19 @@ pub async fn delete_scene(app: tauri::AppHandle, scene_name: String) -> Result<(
     Ok(())
 }
 
+/// 读取要导出的个人场景，去掉代理地址中的认证信息（旧版场景文件中可能包含）
+fn read_exportable_scenes(app: &tauri::AppHandle) -> Result<Vec<Scene>, String> {
+    let mut scenes = read_personal_scenes(app)?;
+    for proxy in scenes.iter_mut().filter_map(|s| s.proxy_config.as_mut()) {
+        proxy.server = crate::proxy::strip_proxy_credentials(&proxy.server);
+    }
+    Ok(scenes)
+}
+
 /// 导出所有场景到指定文件（JSON）
 #[tauri::command]
 pub async fn export_scenes(app: tauri::AppHandle, file_path: String) -> Result<(), String> {
-    let scenes = get_scenes(app).await?;
+    let scenes = read_exportable_scenes(&app)?;
     let content = serde_json::to_string_pretty(&scenes)
         .map_err(|e| format!("序列化场景列表失败: {}", e))?;
     fs::write(&file_path, content)
@@ -435,17 +604,25 @@ pub async fn export_scenes(app: tauri::AppHandle, file_path: String) -> Result<(
     Ok(())
 }
 
-/// 从指定文件导入场景列表（JSON），会覆盖同名场景
+/// 从指定文件导入场景列表（JSON 或场景包），会覆盖同名场景
+///
+/// 场景包必须由受信任的发布者签名，签名不符（被篡改）时拒绝导入；
+/// 加密的场景包需要提供 `passphrase`。
+/// 导入场景包时返回发布者名称，普通 JSON 返回 `None`。
 #[tauri::command]
-pub async fn import_scenes(app: tauri::AppHandle, file_path: String) -> Result<(), String> {
+pub async fn import_scenes(
+    app: tauri::AppHandle,
+    file_path: String,
+    passphrase: Option<String>,
+) -> Result<Option<String>, String> {
     let scenes_dir = ensure_scenes_dir(&app)?;
     let content = fs::read_to_string(&file_path)
         .map_err(|e| format!("读取导入文件失败: {}", e))?;
     
-    let imported: Vec<Scene> = serde_json::from_str(&content)
-        .map_err(|e| format!("解析导入文件失败: {}", e))?;
+    let (imported, publisher) = parse_imported_scenes(&app, &content, passphrase.as_deref())?;
     
-    for scene in imported {
+    for mut scene in imported {
+        scene.source = None;
         let scene_file = scenes_dir.join(format!("{}.json", scene.name));
         let scene_content = serde_json::to_string_pretty(&scene)
             .map_err(|e| format!("序列化场景失败: {}", e))?;
@@ -453,26 +630,30 @@ pub async fn import_scenes(app: tauri::AppHandle, file_path: String) -> Result<(
             .map_err(|e| format!("写入场景文件失败: {}", e))?;
     }
     
-    Ok(())
+    Ok(publisher)
 }
 
 /// 以 JSON 字符串形式导出所有场景（给前端下载用）
 #[tauri::command]
 pub async fn export_scenes_json(app: tauri::AppHandle) -> Result<String, String> {
-    let scenes = get_scenes(app).await?;
+    let scenes = read_exportable_scenes(&app)?;
     let content = serde_json::to_string_pretty(&scenes)
         .map_err(|e| format!("序列化场景列表失败: {}", e))?;
     Ok(content)
 }
 
-/// 从 JSON 字符串导入场景列表（给前端上传用），会覆盖同名场景
+/// 从 JSON 字符串（或场景包内容）导入场景列表（给前端上传用），会覆盖同名场景
 #[tauri::command]
-pub async fn import_scenes_json(app: tauri::AppHandle, json: String) -> Result<(), String> {
+pub async fn import_scenes_json(
+    app: tauri::AppHandle,
+    json: String,
+    passphrase: Option<String>,
+) -> Result<Option<String>, String> {
     let scenes_dir = ensure_scenes_dir(&app)?;
-    let imported: Vec<Scene> = serde_json::from_str(&json)
-        .map_err(|e| format!("解析导入数据失败: {}", e))?;
+    let (imported, publisher) = parse_imported_scenes(&app, &json, passphrase.as_deref())?;
     
-    for scene in imported {
+    for mut scene in imported {
+        scene.source = None;
         let scene_file = scenes_dir.join(format!("{}.json", scene.name));
         let scene_content = serde_json::to_string_pretty(&scene)
             .map_err(|e| format!("序列化场景失败: {}", e))?;
@@ -480,5 +661,270 @@ pub async fn import_scenes_json(app: tauri::AppHandle, json: String) -> Result<(
             .map_err(|e| format!("写入场景文件失败: {}", e))?;
     }
     
+    Ok(publisher)
+}
+
+/// 解析导入内容：场景包先校验签名（必要时解密），否则按旧版 JSON 场景列表解析。
+/// 同时返回场景包的发布者名称
+fn parse_imported_scenes(
+    app: &tauri::AppHandle,
+    content: &str,
+    passphrase: Option<&str>,
+) -> Result<(Vec<Scene>, Option<String>), String> {
+    if !bundle::is_bundle(content) {
+        let scenes = serde_json::from_str(content).map_err(|e| format!("解析导入数据失败: {}", e))?;
+        return Ok((scenes, None));
+    }
+
+    let trusted = trusted_publishers_with_self(app)?;
+    let opened = bundle::open(content, &trusted, passphrase)?;
+    let scenes = serde_json::from_slice(&opened.payload).map_err(|e| format!("解析场景包内容失败: {}", e))?;
+    Ok((scenes, Some(opened.publisher_name)))
+}
+
+/// 读取本机发布者密钥，不存在时自动生成
+fn load_publisher_key(app: &tauri::AppHandle) -> Result<bundle::PublisherKey, String> {
+    let path = storage::app_data_file(app, "publisher_key.json")?;
+    let key: bundle::PublisherKey = storage::load_json(&path)?;
+    if !key.secret_key.is_empty() {
+        return Ok(key);
+    }
+    let key = bundle::generate_publisher_key("IP Switch");
+    storage::save_json(&path, &key)?;
+    Ok(key)
+}
+
+fn load_trusted_publishers(app: &tauri::AppHandle) -> Result<Vec<TrustedPublisher>, String> {
+    storage::load_json(&storage::app_data_file(app, "trusted_publishers.json")?)
+}
+
+/// 受信任列表 + 本机公钥（自己导出的场景包总是可以导入）
+fn trusted_publishers_with_self(app: &tauri::AppHandle) -> Result<Vec<TrustedPublisher>, String> {
+    let key = load_publisher_key(app)?;
+    let mut trusted = load_trusted_publishers(app)?;
+    trusted.push(TrustedPublisher {
+        name: key.name.clone(),
+        public_key: bundle::public_key_of(&key)?,
+    });
+    Ok(trusted)
+}
+
+/// 将所有场景导出为签名的场景包，提供 `passphrase` 时同时加密
+#[tauri::command]
+pub async fn export_scenes_bundle(
+    app: tauri::AppHandle,
+    file_path: String,
+    passphrase: Option<String>,
+) -> Result<(), String> {
+    let key = load_publisher_key(&app)?;
+    let scenes = read_exportable_scenes(&app)?;
+    let payload = serde_json::to_vec(&scenes)
+        .map_err(|e| format!("序列化场景列表失败: {}", e))?;
+
+    let content = bundle::seal(&payload, &key, passphrase.as_deref())?;
+    fs::write(&file_path, content)
+        .map_err(|e| format!("写入导出文件失败: {}", e))?;
+    Ok(())
+}
+
+/// 获取本机发布者公钥（分发给需要导入场景包的同事）
+#[tauri::command]
+pub async fn get_publisher_key(app: tauri::AppHandle) -> Result<TrustedPublisher, String> {
+    let key = load_publisher_key(&app)?;
+    Ok(TrustedPublisher {
+        name: key.name.clone(),
+        public_key: bundle::public_key_of(&key)?,
+    })
+}
+
+#[tauri::command]
+pub async fn list_trusted_publishers(app: tauri::AppHandle) -> Result<Vec<TrustedPublisher>, String> {
+    load_trusted_publishers(&app)
+}
+
+/// 添加受信任的发布者（同一公钥重复添加时更新名称）
+#[tauri::command]
+pub async fn add_trusted_publisher(
+    app: tauri::AppHandle,
+    name: String,
+    public_key: String,
+) -> Result<(), String> {
+    let public_key = bundle::normalize_public_key(&public_key)?;
+    let mut trusted = load_trusted_publishers(&app)?;
+    trusted.retain(|t| t.public_key != public_key);
+    trusted.push(TrustedPublisher { name, public_key });
+    storage::save_json(&storage::app_data_file(&app, "trusted_publishers.json")?, &trusted)
+}
+
+#[tauri::command]
+pub async fn remove_trusted_publisher(app: tauri::AppHandle, public_key: String) -> Result<(), String> {
+    let public_key = bundle::normalize_public_key(&public_key)?;
+    let mut trusted = load_trusted_publishers(&app)?;
+    trusted.retain(|t| t.public_key != public_key);
+    storage::save_json(&storage::app_data_file(&app, "trusted_publishers.json")?, &trusted)
+}
+
+fn load_scene_sources(app: &tauri::AppHandle) -> Result<Vec<SceneSource>, String> {
+    storage::load_json(&storage::app_data_file(app, "scene_sources.json")?)
+}
+
+fn save_scene_sources(app: &tauri::AppHandle, sources: &[SceneSource]) -> Result<(), String> {
+    storage::save_json(&storage::app_data_file(app, "scene_sources.json")?, &sources)
+}
+
+/// 在指定来源中按名称查找场景（来源中的文件名不一定与场景名一致）
+fn find_source_scene(app: &tauri::AppHandle, source: &str, scene_name: &str) -> Result<Scene, String> {
+    let source = load_scene_sources(app)?
+        .into_iter()
+        .find(|s| s.name == source)
+        .ok_or_else(|| format!("找不到场景来源: {}", source))?;
+    sources::read_source_scenes(&source)?
+        .into_iter()
+        .find(|s| s.name == scene_name)
+        .ok_or_else(|| format!("场景来源 {} 中找不到场景: {}", source.name, scene_name))
+}
+
+#[tauri::command]
+pub async fn list_scene_sources(app: tauri::AppHandle) -> Result<Vec<SceneSource>, String> {
+    load_scene_sources(&app)
+}
+
+/// 注册只读场景来源（网络共享目录或本地 git 工作副本）
+#[tauri::command]
+pub async fn add_scene_source(
+    app: tauri::AppHandle,
+    name: String,
+    path: String,
+    kind: SceneSourceKind,
+) -> Result<SceneSourceStatus, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("场景来源名称不能为空".to_string());
+    }
+    if !PathBuf::from(&path).is_dir() {
+        return Err(format!("目录不存在: {}", path));
+    }
+
+    let mut sources = load_scene_sources(&app)?;
+    if sources.iter().any(|s| s.name == name) {
+        return Err(format!("场景来源 {} 已存在", name));
+    }
+
+    let mut source = SceneSource {
+        name,
+        kind,
+        path,
+        fingerprint: None,
+        last_refreshed: None,
+        last_error: None,
+    };
+    let status = sources::refresh_source(&mut source);
+    sources.push(source);
+    save_scene_sources(&app, &sources)?;
+    Ok(status)
+}
+
+#[tauri::command]
+pub async fn remove_scene_source(app: tauri::AppHandle, name: String) -> Result<(), String> {
+    let mut sources = load_scene_sources(&app)?;
+    sources.retain(|s| s.name != name);
+    save_scene_sources(&app, &sources)
+}
+
+/// 刷新所有场景来源（git 来源会先 pull），返回每个来源是否有变化
+#[tauri::command]
+pub async fn refresh_scene_sources(app: tauri::AppHandle) -> Result<Vec<SceneSourceStatus>, String> {
+    let mut sources = load_scene_sources(&app)?;
+    // git pull / 网络共享访问可能较慢，放到阻塞线程中执行
+    let (sources, statuses) = tokio::task::spawn_blocking(move || {
+        let statuses: Vec<SceneSourceStatus> = sources.iter_mut().map(sources::refresh_source).collect();
+        (sources, statuses)
+    })
+    .await
+    .map_err(|e| format!("刷新场景来源失败: {}", e))?;
+
+    save_scene_sources(&app, &sources)?;
+    Ok(statuses)
+}
+
+/// 将共享场景复制到个人目录（可指定新名称），之后即可编辑
+#[tauri::command]
+pub async fn fork_scene(
+    app: tauri::AppHandle,
+    source: String,
+    scene_name: String,
+    new_name: Option<String>,
+) -> Result<(), String> {
+    let mut scene = find_source_scene(&app, &source, &scene_name)?;
+    scene.source = None;
+    if let Some(new_name) = new_name.filter(|n| !n.trim().is_empty()) {
+        scene.name = new_name.trim().to_string();
+    }
+
+    let scenes_dir = ensure_scenes_dir(&app)?;
+    let scene_file = scenes_dir.join(format!("{}.json", scene.name));
+    if scene_file.exists() {
+        return Err(format!("个人目录中已存在同名场景: {}", scene.name));
+    }
+
+    let content = serde_json::to_string_pretty(&scene)
+        .map_err(|e| format!("序列化场景失败: {}", e))?;
+    fs::write(&scene_file, content)
+        .map_err(|e| format!("保存场景失败: {}", e))?;
+    Ok(())
+}
+
+/// 按标签、网卡、子网或关键字搜索场景（包括只读来源中的场景）
+#[tauri::command]
+pub async fn search_scenes(app: tauri::AppHandle, query: SceneQuery) -> Result<Vec<Scene>, String> {
+    let mut result = Vec::new();
+    for scene in get_scenes(app).await? {
+        if search::matches(&scene, &query)? {
+            result.push(scene);
+        }
+    }
+    Ok(result)
+}
+
+/// 将场景导出为 Linux 网络配置文件（NetworkManager keyfile / netplan / systemd-networkd）
+///
+/// `interface_map` 用于把 Windows 网卡名映射为 Linux 网卡名，未映射的网卡保持原名。
+#[tauri::command]
+pub async fn export_scene_linux(
+    app: tauri::AppHandle,
+    scene_name: String,
+    format: LinuxNetFormat,
+    interface_map: Option<HashMap<String, String>>,
+) -> Result<Vec<LinuxNetFile>, String> {
+    let scenes_dir = ensure_scenes_dir(&app)?;
+    let scene_file = scenes_dir.join(format!("{}.json", scene_name));
+
+    let content = fs::read_to_string(&scene_file)
+        .map_err(|e| format!("读取场景文件失败: {}", e))?;
+    let scene: Scene = serde_json::from_str(&content)
+        .map_err(|e| format!("解析场景文件失败: {}", e))?;
+
+    linux_formats::export_scene(&scene, format, &interface_map.unwrap_or_default())
+}
+
+/// 从 Linux 网络配置文件导入为场景，会覆盖同名场景
+#[tauri::command]
+pub async fn import_scene_linux(
+    app: tauri::AppHandle,
+    scene_name: String,
+    format: LinuxNetFormat,
+    files: Vec<LinuxNetFile>,
+) -> Result<(), String> {
+    let scenes_dir = ensure_scenes_dir(&app)?;
+    let mut scene = linux_formats::import_scene(&scene_name, format, &files)?;
+    scene.created_at = Some(now_millis());
+    scene.modified_at = scene.created_at;
+
+    let scene_file = scenes_dir.join(format!("{}.json", scene.name));
+    let scene_content = serde_json::to_string_pretty(&scene)
+        .map_err(|e| format!("序列化场景失败: {}", e))?;
+    fs::write(&scene_file, scene_content)
+        .map_err(|e| format!("写入场景文件失败: {}", e))?;
+
     Ok(())
 }
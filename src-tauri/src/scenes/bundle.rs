@@ -0,0 +1,304 @@
+//! 签名（可选加密）的场景包
+//!
+//! 场景包是一个 JSON 文件：`body` 为原样保存的 JSON 字符串，`signature` 是发布者
+//! 用 Ed25519 私钥对 `body` 字节的签名。设置口令时，`body.payload` 为经
+//! Argon2id 派生密钥、XChaCha20-Poly1305 加密后的场景列表；签名覆盖密文，
+//! 因此无需口令也能校验包是否被篡改。
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+const BUNDLE_FORMAT: &str = "ip-switch-scene-bundle";
+const BUNDLE_VERSION: u32 = 1;
+
+/// 本机的发布者密钥（私钥只保存在应用数据目录中）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PublisherKey {
+    pub name: String,
+    pub secret_key: String,
+}
+
+/// 受信任的发布者公钥
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrustedPublisher {
+    pub name: String,
+    pub public_key: String,
+}
+
+/// 校验并解密后的场景包内容
+#[derive(Debug)]
+pub struct OpenedBundle {
+    pub publisher_name: String,
+    pub payload: Vec<u8>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BundleFile {
+    format: String,
+    version: u32,
+    body: String,
+    signature: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BundleBody {
+    publisher_name: String,
+    publisher_key: String,
+    created_at: u64,
+    encryption: Option<EncryptionParams>,
+    payload: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct EncryptionParams {
+    cipher: String,
+    kdf: String,
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+    salt: String,
+    nonce: String,
+}
+
+/// 判断内容是否为场景包（否则按旧版 JSON 场景列表处理）
+pub fn is_bundle(content: &str) -> bool {
+    #[derive(Deserialize)]
+    struct Probe {
+        format: Option<String>,
+    }
+    serde_json::from_str::<Probe>(content)
+        .map(|p| p.format.as_deref() == Some(BUNDLE_FORMAT))
+        .unwrap_or(false)
+}
+
+/// 生成新的发布者密钥
+pub fn generate_publisher_key(name: &str) -> PublisherKey {
+    let signing_key = SigningKey::generate(&mut OsRng);
+    PublisherKey {
+        name: name.to_string(),
+        secret_key: BASE64.encode(signing_key.to_bytes()),
+    }
+}
+
+/// 发布者密钥对应的公钥（Base64）
+pub fn public_key_of(key: &PublisherKey) -> Result<String, String> {
+    Ok(BASE64.encode(signing_key(key)?.verifying_key().to_bytes()))
+}
+
+/// 校验公钥格式，返回规范化后的 Base64 字符串
+pub fn normalize_public_key(public_key: &str) -> Result<String, String> {
+    Ok(BASE64.encode(verifying_key(public_key)?.to_bytes()))
+}
+
+/// 打包：可选加密，然后签名
+pub fn seal(payload: &[u8], key: &PublisherKey, passphrase: Option<&str>) -> Result<String, String> {
+    let signing_key = signing_key(key)?;
+
+    let (encryption, payload) = match passphrase.filter(|p| !p.is_empty()) {
+        Some(passphrase) => {
+            let params = Params::default();
+            let mut salt = [0u8; 16];
+            let mut nonce = [0u8; 24];
+            OsRng.fill_bytes(&mut salt);
+            OsRng.fill_bytes(&mut nonce);
+
+            let cipher = derive_cipher(passphrase, &salt, params.m_cost(), params.t_cost(), params.p_cost())?;
+            let ciphertext = cipher
+                .encrypt(XNonce::from_slice(&nonce), payload)
+                .map_err(|_| "加密场景包失败".to_string())?;
+            let encryption = EncryptionParams {
+                cipher: "xchacha20poly1305".to_string(),
+                kdf: "argon2id".to_string(),
+                m_cost: params.m_cost(),
+                t_cost: params.t_cost(),
+                p_cost: params.p_cost(),
+                salt: BASE64.encode(salt),
+                nonce: BASE64.encode(nonce),
+            };
+            (Some(encryption), ciphertext)
+        }
+        None => (None, payload.to_vec()),
+    };
+
+    let created_at = super::now_millis();
+    let body = serde_json::to_string(&BundleBody {
+        publisher_name: key.name.clone(),
+        publisher_key: BASE64.encode(signing_key.verifying_key().to_bytes()),
+        created_at,
+        encryption,
+        payload: BASE64.encode(payload),
+    })
+    .map_err(|e| format!("序列化场景包失败: {}", e))?;
+
+    let signature = signing_key.sign(body.as_bytes());
+    serde_json::to_string_pretty(&BundleFile {
+        format: BUNDLE_FORMAT.to_string(),
+        version: BUNDLE_VERSION,
+        body,
+        signature: BASE64.encode(signature.to_bytes()),
+    })
+    .map_err(|e| format!("序列化场景包失败: {}", e))
+}
+
+/// 解包：校验签名与发布者是否受信任，必要时解密
+pub fn open(
+    content: &str,
+    trusted: &[TrustedPublisher],
+    passphrase: Option<&str>,
+) -> Result<OpenedBundle, String> {
+    let file: BundleFile = serde_json::from_str(content)
+        .map_err(|e| format!("解析场景包失败: {}", e))?;
+    if file.format != BUNDLE_FORMAT {
+        return Err("不是有效的场景包".to_string());
+    }
+    if file.version != BUNDLE_VERSION {
+        return Err(format!("不支持的场景包版本: {}", file.version));
+    }
+
+    let body: BundleBody = serde_json::from_str(&file.body)
+        .map_err(|_| "场景包内容已损坏".to_string())?;
+
+    // 先确认发布者受信任，再校验签名
+    let publisher = trusted
+        .iter()
+        .find(|t| t.public_key == body.publisher_key)
+        .ok_or_else(|| format!("场景包发布者 \"{}\" 不在受信任列表中", body.publisher_name))?;
+
+    let verifying_key = verifying_key(&body.publisher_key)?;
+    let signature_bytes: [u8; 64] = BASE64
+        .decode(&file.signature)
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| "场景包签名格式无效".to_string())?;
+    verifying_key
+        .verify(file.body.as_bytes(), &Signature::from_bytes(&signature_bytes))
+        .map_err(|_| "场景包签名校验失败，文件可能已被篡改".to_string())?;
+
+    let payload = BASE64
+        .decode(&body.payload)
+        .map_err(|_| "场景包内容已损坏".to_string())?;
+
+    let payload = match &body.encryption {
+        Some(enc) => {
+            let passphrase = passphrase
+                .filter(|p| !p.is_empty())
+                .ok_or_else(|| "场景包已加密，请输入口令".to_string())?;
+            if enc.cipher != "xchacha20poly1305" || enc.kdf != "argon2id" {
+                return Err(format!("不支持的加密方式: {} / {}", enc.cipher, enc.kdf));
+            }
+            let salt = BASE64.decode(&enc.salt).map_err(|_| "场景包内容已损坏".to_string())?;
+            let nonce = BASE64.decode(&enc.nonce).map_err(|_| "场景包内容已损坏".to_string())?;
+            if nonce.len() != 24 {
+                return Err("场景包内容已损坏".to_string());
+            }
+            let cipher = derive_cipher(passphrase, &salt, enc.m_cost, enc.t_cost, enc.p_cost)?;
+            cipher
+                .decrypt(XNonce::from_slice(&nonce), payload.as_slice())
+                .map_err(|_| "口令错误，无法解密场景包".to_string())?
+        }
+        None => payload,
+    };
+
+    Ok(OpenedBundle {
+        publisher_name: publisher.name.clone(),
+        payload,
+    })
+}
+
+fn signing_key(key: &PublisherKey) -> Result<SigningKey, String> {
+    let bytes: [u8; 32] = BASE64
+        .decode(&key.secret_key)
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| "发布者私钥已损坏".to_string())?;
+    Ok(SigningKey::from_bytes(&bytes))
+}
+
+fn verifying_key(public_key: &str) -> Result<VerifyingKey, String> {
+    let bytes: [u8; 32] = BASE64
+        .decode(public_key.trim())
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| "无效的发布者公钥".to_string())?;
+    VerifyingKey::from_bytes(&bytes).map_err(|_| "无效的发布者公钥".to_string())
+}
+
+fn derive_cipher(
+    passphrase: &str,
+    salt: &[u8],
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+) -> Result<XChaCha20Poly1305, String> {
+    let params = Params::new(m_cost, t_cost, p_cost, Some(32))
+        .map_err(|e| format!("无效的密钥派生参数: {}", e))?;
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("派生加密密钥失败: {}", e))?;
+    Ok(XChaCha20Poly1305::new((&key).into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trusted(key: &PublisherKey) -> Vec<TrustedPublisher> {
+        vec![TrustedPublisher {
+            name: key.name.clone(),
+            public_key: public_key_of(key).unwrap(),
+        }]
+    }
+
+    #[test]
+    fn test_signed_bundle_round_trip() {
+        let key = generate_publisher_key("IT");
+        let bundle = seal(b"[]", &key, None).unwrap();
+        assert!(is_bundle(&bundle));
+
+        let opened = open(&bundle, &trusted(&key), None).unwrap();
+        assert_eq!(opened.payload, b"[]");
+        assert_eq!(opened.publisher_name, "IT");
+    }
+
+    #[test]
+    fn test_encrypted_bundle_requires_passphrase() {
+        let key = generate_publisher_key("IT");
+        let bundle = seal(b"[{\"name\":\"x\"}]", &key, Some("secret")).unwrap();
+        assert!(!bundle.contains("\\\"name\\\""));
+
+        assert!(open(&bundle, &trusted(&key), None).is_err());
+        assert!(open(&bundle, &trusted(&key), Some("wrong")).is_err());
+        let opened = open(&bundle, &trusted(&key), Some("secret")).unwrap();
+        assert_eq!(opened.payload, b"[{\"name\":\"x\"}]");
+    }
+
+    #[test]
+    fn test_tampered_bundle_is_rejected() {
+        let key = generate_publisher_key("IT");
+        let bundle = seal(b"[]", &key, None).unwrap();
+        let mut file: BundleFile = serde_json::from_str(&bundle).unwrap();
+        let mut body: BundleBody = serde_json::from_str(&file.body).unwrap();
+        body.payload = BASE64.encode(b"[{\"name\":\"evil\"}]");
+        file.body = serde_json::to_string(&body).unwrap();
+        let tampered = serde_json::to_string(&file).unwrap();
+
+        let err = open(&tampered, &trusted(&key), None).unwrap_err();
+        assert!(err.contains("篡改"));
+    }
+
+    #[test]
+    fn test_untrusted_publisher_is_rejected() {
+        let key = generate_publisher_key("IT");
+        let other = generate_publisher_key("contractor");
+        let bundle = seal(b"[]", &other, None).unwrap();
+        assert!(open(&bundle, &trusted(&key), None).is_err());
+    }
+}
@@ -0,0 +1,579 @@
+//! 场景与 Linux 网络配置格式之间的互相转换
+//!
+//! 支持 NetworkManager keyfile（*.nmconnection）、netplan YAML 以及
+//! systemd-networkd 的 `.network` 单元文件。场景中的网卡名是 Windows 名称，
+//! 导出时可以通过 `interface_map` 映射为 Linux 网卡名（如 "以太网" -> "eth0"）。
+
+use super::{NetworkConfig, Scene};
+use crate::network::{prefix_to_subnet, subnet_to_prefix};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinuxNetFormat {
+    NetworkManager,
+    Netplan,
+    Networkd,
+}
+
+/// 导出/导入的单个配置文件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinuxNetFile {
+    pub file_name: String,
+    pub content: String,
+}
+
+/// 将场景导出为指定格式的配置文件列表
+pub fn export_scene(
+    scene: &Scene,
+    format: LinuxNetFormat,
+    interface_map: &HashMap<String, String>,
+) -> Result<Vec<LinuxNetFile>, String> {
+    // 按网卡名排序，保证输出稳定
+    let mut adapters: Vec<(&String, &NetworkConfig)> = scene.network_configs.iter().collect();
+    adapters.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut mapped = Vec::with_capacity(adapters.len());
+    for (adapter, config) in adapters {
+        let iface = interface_map.get(adapter).unwrap_or(adapter);
+        mapped.push((iface.as_str(), config, static_address(adapter, config)?));
+    }
+
+    match format {
+        LinuxNetFormat::NetworkManager => Ok(mapped
+            .iter()
+            .map(|(iface, config, address)| LinuxNetFile {
+                file_name: format!("{}.nmconnection", file_safe(iface)),
+                content: to_keyfile(&scene.name, iface, config, address.as_deref()),
+            })
+            .collect()),
+        LinuxNetFormat::Networkd => Ok(mapped
+            .iter()
+            .map(|(iface, config, address)| LinuxNetFile {
+                file_name: format!("10-{}.network", file_safe(iface)),
+                content: to_networkd(iface, config, address.as_deref()),
+            })
+            .collect()),
+        LinuxNetFormat::Netplan => {
+            let mut ethernets = BTreeMap::new();
+            for (iface, config, address) in &mapped {
+                ethernets.insert(iface.to_string(), to_netplan_interface(config, address.as_deref()));
+            }
+            let doc = NetplanDoc {
+                network: NetplanNetwork {
+                    version: 2,
+                    renderer: Some("networkd".to_string()),
+                    ethernets,
+                },
+            };
+            let content = serde_yaml::to_string(&doc)
+                .map_err(|e| format!("生成 netplan 配置失败: {}", e))?;
+            Ok(vec![LinuxNetFile {
+                file_name: "99-ip-switch.yaml".to_string(),
+                content: format!("# 由 IP Switch 场景 \"{}\" 导出\n{}", scene.name, content),
+            }])
+        }
+    }
+}
+
+/// 从指定格式的配置文件导入为场景
+pub fn import_scene(
+    scene_name: &str,
+    format: LinuxNetFormat,
+    files: &[LinuxNetFile],
+) -> Result<Scene, String> {
+    let mut network_configs = HashMap::new();
+    for file in files {
+        let parsed = match format {
+            LinuxNetFormat::NetworkManager => from_keyfile(&file.content)?,
+            LinuxNetFormat::Networkd => from_networkd(&file.content)?,
+            LinuxNetFormat::Netplan => from_netplan(&file.content)?,
+        };
+        for (iface, config) in parsed {
+            network_configs.insert(iface, config);
+        }
+    }
+
+    if network_configs.is_empty() {
+        return Err("导入的文件中没有找到任何网卡配置".to_string());
+    }
+
+    Ok(Scene {
+        name: scene_name.to_string(),
+        network_configs,
+        hosts_content: None,
+        proxy_config: None,
+        tray_color: None,
+        ..Default::default()
+    })
+}
+
+/// 静态配置转为 CIDR 地址（如 "192.168.1.10/24"），DHCP 返回 None
+fn static_address(adapter: &str, config: &NetworkConfig) -> Result<Option<String>, String> {
+    if config.is_dhcp {
+        return Ok(None);
+    }
+    let ip = config
+        .ip
+        .as_deref()
+        .filter(|s| !s.trim().is_empty())
+        .ok_or_else(|| format!("网卡 {} 的静态配置缺少 IP 地址", adapter))?;
+    let subnet = config
+        .subnet
+        .as_deref()
+        .filter(|s| !s.trim().is_empty())
+        .ok_or_else(|| format!("网卡 {} 的静态配置缺少子网掩码", adapter))?;
+    let prefix = subnet_to_prefix(subnet)?;
+    Ok(Some(format!("{}/{}", ip.trim(), prefix)))
+}
+
+/// CIDR 地址拆分为 IP 与点分十进制子网掩码
+fn split_address(address: &str) -> Result<(String, String), String> {
+    let (ip, prefix) = address
+        .split_once('/')
+        .ok_or_else(|| format!("地址缺少前缀长度: {}", address))?;
+    let prefix: u8 = prefix
+        .trim()
+        .parse()
+        .map_err(|_| format!("无效的前缀长度: {}", address))?;
+    let subnet = if prefix == 0 {
+        Some("0.0.0.0".to_string())
+    } else {
+        prefix_to_subnet(prefix)
+    }
+    .ok_or_else(|| format!("无效的前缀长度: {}", address))?;
+    Ok((ip.trim().to_string(), subnet))
+}
+
+fn non_empty_dns(config: &NetworkConfig) -> Option<&Vec<String>> {
+    config.dns.as_ref().filter(|d| !d.is_empty())
+}
+
+/// 网卡名中可能包含空格或中文，文件名里统一替换掉不安全字符
+fn file_safe(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' || c == '.' { c } else { '_' })
+        .collect()
+}
+
+fn build_config(address: Option<&str>, gateway: Option<String>, dns: Vec<String>) -> Result<NetworkConfig, String> {
+    let dns = if dns.is_empty() { None } else { Some(dns) };
+    match address {
+        Some(address) => {
+            let (ip, subnet) = split_address(address)?;
+            Ok(NetworkConfig {
+                is_dhcp: false,
+                ip: Some(ip),
+                subnet: Some(subnet),
+                gateway,
+                dns,
+            })
+        }
+        None => Ok(NetworkConfig {
+            is_dhcp: true,
+            ip: None,
+            subnet: None,
+            gateway: None,
+            dns,
+        }),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// INI 风格（keyfile / networkd 共用）
+// ---------------------------------------------------------------------------
+
+/// 解析 INI 风格文本为 (section, key, value) 列表，保留重复键
+fn parse_ini(content: &str) -> Vec<(String, String, String)> {
+    let mut section = String::new();
+    let mut items = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if line.starts_with('[') && line.ends_with(']') {
+            section = line[1..line.len() - 1].trim().to_string();
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            items.push((section.clone(), key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    items
+}
+
+// ---------------------------------------------------------------------------
+// NetworkManager keyfile
+// ---------------------------------------------------------------------------
+
+fn to_keyfile(scene_name: &str, iface: &str, config: &NetworkConfig, address: Option<&str>) -> String {
+    let mut out = String::new();
+    out.push_str("[connection]\n");
+    out.push_str(&format!("id={} ({})\n", scene_name, iface));
+    out.push_str("type=ethernet\n");
+    out.push_str(&format!("interface-name={}\n", iface));
+    out.push_str("autoconnect=true\n\n");
+
+    out.push_str("[ipv4]\n");
+    match address {
+        Some(address) => {
+            out.push_str("method=manual\n");
+            match config.gateway.as_deref().filter(|g| !g.is_empty()) {
+                Some(gateway) => out.push_str(&format!("address1={},{}\n", address, gateway)),
+                None => out.push_str(&format!("address1={}\n", address)),
+            }
+        }
+        None => out.push_str("method=auto\n"),
+    }
+    if let Some(dns) = non_empty_dns(config) {
+        out.push_str(&format!("dns={};\n", dns.join(";")));
+        if address.is_none() {
+            out.push_str("ignore-auto-dns=true\n");
+        }
+    }
+    out.push_str("\n[ipv6]\nmethod=auto\n");
+    out
+}
+
+fn from_keyfile(content: &str) -> Result<Vec<(String, NetworkConfig)>, String> {
+    let items = parse_ini(content);
+    let get = |section: &str, key: &str| {
+        items
+            .iter()
+            .find(|(s, k, _)| s == section && k == key)
+            .map(|(_, _, v)| v.clone())
+    };
+
+    let iface = get("connection", "interface-name")
+        .or_else(|| get("connection", "id"))
+        .ok_or_else(|| "keyfile 缺少 interface-name".to_string())?;
+    let method = get("ipv4", "method").unwrap_or_else(|| "auto".to_string());
+
+    // address1=IP/prefix[,gateway]，网关也可能单独写在 gateway= 中
+    let (address, mut gateway) = match items
+        .iter()
+        .find(|(s, k, _)| s == "ipv4" && (k == "address1" || k == "addresses"))
+    {
+        Some((_, _, value)) if method == "manual" => {
+            let mut parts = value.splitn(2, ',');
+            let address = parts.next().unwrap_or("").trim().to_string();
+            let gateway = parts.next().map(|g| g.trim().to_string()).filter(|g| !g.is_empty());
+            (Some(address), gateway)
+        }
+        None if method == "manual" => {
+            return Err(format!("{} 为手动配置，但缺少 address1", iface));
+        }
+        _ => (None, None),
+    };
+    if gateway.is_none() && address.is_some() {
+        gateway = get("ipv4", "gateway");
+    }
+
+    let dns = get("ipv4", "dns")
+        .map(|v| {
+            v.split([';', ','])
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(vec![(iface, build_config(address.as_deref(), gateway, dns)?)])
+}
+
+// ---------------------------------------------------------------------------
+// systemd-networkd
+// ---------------------------------------------------------------------------
+
+fn to_networkd(iface: &str, config: &NetworkConfig, address: Option<&str>) -> String {
+    let mut out = String::new();
+    out.push_str("[Match]\n");
+    out.push_str(&format!("Name={}\n\n", iface));
+    out.push_str("[Network]\n");
+    match address {
+        Some(address) => {
+            out.push_str(&format!("Address={}\n", address));
+            if let Some(gateway) = config.gateway.as_deref().filter(|g| !g.is_empty()) {
+                out.push_str(&format!("Gateway={}\n", gateway));
+            }
+        }
+        None => out.push_str("DHCP=ipv4\n"),
+    }
+    if let Some(dns) = non_empty_dns(config) {
+        for server in dns {
+            out.push_str(&format!("DNS={}\n", server));
+        }
+        if address.is_none() {
+            // 使用自定义 DNS 时忽略 DHCP 下发的 DNS
+            out.push_str("\n[DHCPv4]\nUseDNS=false\n");
+        }
+    }
+    out
+}
+
+fn from_networkd(content: &str) -> Result<Vec<(String, NetworkConfig)>, String> {
+    let items = parse_ini(content);
+    let values = |section: &str, key: &str| -> Vec<String> {
+        items
+            .iter()
+            .filter(|(s, k, _)| s == section && k == key)
+            .flat_map(|(_, _, v)| v.split_whitespace().map(|s| s.to_string()).collect::<Vec<_>>())
+            .collect()
+    };
+
+    let iface = values("Match", "Name")
+        .into_iter()
+        .next()
+        .ok_or_else(|| "networkd 配置缺少 [Match] Name=".to_string())?;
+
+    let dhcp = values("Network", "DHCP")
+        .first()
+        .map(|v| matches!(v.as_str(), "yes" | "ipv4" | "true"))
+        .unwrap_or(false);
+    // 只取第一个 IPv4 地址，场景只记录一个地址
+    let address = if dhcp {
+        None
+    } else {
+        values("Network", "Address").into_iter().find(|a| !a.contains(':'))
+    };
+    let gateway = if address.is_some() {
+        values("Network", "Gateway").into_iter().find(|g| !g.contains(':'))
+    } else {
+        None
+    };
+    let dns = values("Network", "DNS");
+
+    Ok(vec![(iface, build_config(address.as_deref(), gateway, dns)?)])
+}
+
+// ---------------------------------------------------------------------------
+// netplan
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Serialize, Deserialize)]
+struct NetplanDoc {
+    network: NetplanNetwork,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct NetplanNetwork {
+    #[serde(default = "netplan_version")]
+    version: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    renderer: Option<String>,
+    #[serde(default)]
+    ethernets: BTreeMap<String, NetplanInterface>,
+}
+
+fn netplan_version() -> u8 {
+    2
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct NetplanInterface {
+    #[serde(default)]
+    dhcp4: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    addresses: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    gateway4: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    routes: Vec<NetplanRoute>,
+    #[serde(rename = "dhcp4-overrides", default, skip_serializing_if = "Option::is_none")]
+    dhcp4_overrides: Option<NetplanDhcpOverrides>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    nameservers: Option<NetplanNameservers>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct NetplanRoute {
+    to: String,
+    via: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct NetplanDhcpOverrides {
+    #[serde(rename = "use-dns")]
+    use_dns: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct NetplanNameservers {
+    #[serde(default)]
+    addresses: Vec<String>,
+}
+
+fn to_netplan_interface(config: &NetworkConfig, address: Option<&str>) -> NetplanInterface {
+    let mut iface = NetplanInterface::default();
+    match address {
+        Some(address) => {
+            iface.addresses.push(address.to_string());
+            if let Some(gateway) = config.gateway.as_deref().filter(|g| !g.is_empty()) {
+                iface.routes.push(NetplanRoute {
+                    to: "default".to_string(),
+                    via: gateway.to_string(),
+                });
+            }
+        }
+        None => iface.dhcp4 = true,
+    }
+    if let Some(dns) = non_empty_dns(config) {
+        iface.nameservers = Some(NetplanNameservers { addresses: dns.clone() });
+        if address.is_none() {
+            iface.dhcp4_overrides = Some(NetplanDhcpOverrides { use_dns: false });
+        }
+    }
+    iface
+}
+
+fn from_netplan(content: &str) -> Result<Vec<(String, NetworkConfig)>, String> {
+    let doc: NetplanDoc = serde_yaml::from_str(content)
+        .map_err(|e| format!("解析 netplan 配置失败: {}", e))?;
+
+    let mut result = Vec::new();
+    for (name, iface) in doc.network.ethernets {
+        let address = if iface.dhcp4 {
+            None
+        } else {
+            iface.addresses.iter().find(|a| !a.contains(':')).cloned()
+        };
+        // 新版 netplan 使用 routes: [{to: default, via: ...}]，旧版使用 gateway4
+        let gateway = if address.is_some() {
+            iface
+                .routes
+                .iter()
+                .find(|r| r.to == "default" || r.to == "0.0.0.0/0")
+                .map(|r| r.via.clone())
+                .or(iface.gateway4)
+        } else {
+            None
+        };
+        let dns = iface.nameservers.map(|n| n.addresses).unwrap_or_default();
+        result.push((name, build_config(address.as_deref(), gateway, dns)?));
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_scene() -> Scene {
+        let mut network_configs = HashMap::new();
+        network_configs.insert(
+            "eth0".to_string(),
+            NetworkConfig {
+                is_dhcp: false,
+                ip: Some("192.168.1.10".to_string()),
+                subnet: Some("255.255.255.0".to_string()),
+                gateway: Some("192.168.1.1".to_string()),
+                dns: Some(vec!["8.8.8.8".to_string(), "1.1.1.1".to_string()]),
+            },
+        );
+        network_configs.insert(
+            "wlan0".to_string(),
+            NetworkConfig {
+                is_dhcp: true,
+                ip: None,
+                subnet: None,
+                gateway: None,
+                dns: Some(vec!["223.5.5.5".to_string()]),
+            },
+        );
+        network_configs.insert(
+            "eth1".to_string(),
+            NetworkConfig {
+                is_dhcp: true,
+                ip: None,
+                subnet: None,
+                gateway: None,
+                dns: None,
+            },
+        );
+        Scene {
+            name: "office".to_string(),
+            network_configs,
+            hosts_content: None,
+            proxy_config: None,
+            tray_color: None,
+            ..Default::default()
+        }
+    }
+
+    fn assert_round_trip(format: LinuxNetFormat) {
+        let scene = sample_scene();
+        let files = export_scene(&scene, format, &HashMap::new()).unwrap();
+        let imported = import_scene("office", format, &files).unwrap();
+
+        assert_eq!(imported.network_configs.len(), scene.network_configs.len());
+        for (name, expected) in &scene.network_configs {
+            let actual = &imported.network_configs[name];
+            assert_eq!(actual.is_dhcp, expected.is_dhcp, "{:?} {}", format, name);
+            assert_eq!(actual.ip, expected.ip, "{:?} {}", format, name);
+            assert_eq!(actual.subnet, expected.subnet, "{:?} {}", format, name);
+            assert_eq!(actual.gateway, expected.gateway, "{:?} {}", format, name);
+            assert_eq!(actual.dns, expected.dns, "{:?} {}", format, name);
+        }
+    }
+
+    #[test]
+    fn test_networkmanager_round_trip() {
+        assert_round_trip(LinuxNetFormat::NetworkManager);
+    }
+
+    #[test]
+    fn test_netplan_round_trip() {
+        assert_round_trip(LinuxNetFormat::Netplan);
+    }
+
+    #[test]
+    fn test_networkd_round_trip() {
+        assert_round_trip(LinuxNetFormat::Networkd);
+    }
+
+    #[test]
+    fn test_interface_map_renames_adapters() {
+        let mut scene = sample_scene();
+        let config = scene.network_configs.remove("eth0").unwrap();
+        scene.network_configs.clear();
+        scene.network_configs.insert("以太网 2".to_string(), config);
+
+        let mut map = HashMap::new();
+        map.insert("以太网 2".to_string(), "enp3s0".to_string());
+        let files = export_scene(&scene, LinuxNetFormat::Networkd, &map).unwrap();
+        assert_eq!(files[0].file_name, "10-enp3s0.network");
+        assert!(files[0].content.contains("Name=enp3s0\n"));
+
+        let files = export_scene(&scene, LinuxNetFormat::NetworkManager, &HashMap::new()).unwrap();
+        assert_eq!(files[0].file_name, "以太网_2.nmconnection");
+        let imported = import_scene("x", LinuxNetFormat::NetworkManager, &files).unwrap();
+        assert!(imported.network_configs.contains_key("以太网 2"));
+    }
+
+    #[test]
+    fn test_import_legacy_netplan_gateway4() {
+        let yaml = "network:\n  version: 2\n  ethernets:\n    eth0:\n      addresses: [10.0.0.5/16]\n      gateway4: 10.0.0.1\n      nameservers:\n        addresses: [10.0.0.2]\n";
+        let files = vec![LinuxNetFile { file_name: "01.yaml".to_string(), content: yaml.to_string() }];
+        let scene = import_scene("lab", LinuxNetFormat::Netplan, &files).unwrap();
+        let eth0 = &scene.network_configs["eth0"];
+        assert_eq!(eth0.ip.as_deref(), Some("10.0.0.5"));
+        assert_eq!(eth0.subnet.as_deref(), Some("255.255.0.0"));
+        assert_eq!(eth0.gateway.as_deref(), Some("10.0.0.1"));
+    }
+
+    #[test]
+    fn test_static_without_ip_is_rejected() {
+        let mut scene = sample_scene();
+        scene.network_configs.get_mut("eth0").unwrap().ip = None;
+        assert!(export_scene(&scene, LinuxNetFormat::Netplan, &HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn test_manual_keyfile_without_address_is_rejected() {
+        let content = "[connection]\nid=eth0\ninterface-name=eth0\n\n[ipv4]\nmethod=manual\ndns=10.0.0.2;\n";
+        let files = vec![LinuxNetFile { file_name: "eth0.nmconnection".to_string(), content: content.to_string() }];
+        assert!(import_scene("lab", LinuxNetFormat::NetworkManager, &files).is_err());
+    }
+}
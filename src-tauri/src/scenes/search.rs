@@ -0,0 +1,140 @@
+//! 场景搜索：按关键字、标签、网卡或子网过滤
+
+use super::Scene;
+use serde::{Deserialize, Serialize};
+use std::net::Ipv4Addr;
+
+/// 搜索条件，所有条件之间为“且”的关系，未提供的条件不参与过滤
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SceneQuery {
+    /// 在名称、描述、站点中模糊匹配（不区分大小写）
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub tag: Option<String>,
+    /// 场景中包含该网卡（不区分大小写）
+    #[serde(default)]
+    pub adapter: Option<String>,
+    /// 场景中有静态 IP 落在该子网内，如 "192.168.1.0/24"；也可只写 IP 精确匹配
+    #[serde(default)]
+    pub subnet: Option<String>,
+}
+
+pub fn matches(scene: &Scene, query: &SceneQuery) -> Result<bool, String> {
+    if let Some(text) = non_empty(&query.text) {
+        let text = text.to_lowercase();
+        let hit = [Some(&scene.name), scene.description.as_ref(), scene.site.as_ref()]
+            .into_iter()
+            .flatten()
+            .any(|s| s.to_lowercase().contains(&text));
+        if !hit {
+            return Ok(false);
+        }
+    }
+
+    if let Some(tag) = non_empty(&query.tag) {
+        if !scene.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            return Ok(false);
+        }
+    }
+
+    if let Some(adapter) = non_empty(&query.adapter) {
+        let adapter = adapter.to_lowercase();
+        if !scene.network_configs.keys().any(|name| name.to_lowercase() == adapter) {
+            return Ok(false);
+        }
+    }
+
+    if let Some(subnet) = non_empty(&query.subnet) {
+        let (network, prefix) = parse_cidr(subnet)?;
+        let hit = scene
+            .network_configs
+            .values()
+            .filter_map(|c| c.ip.as_deref())
+            .filter_map(|ip| ip.trim().parse::<Ipv4Addr>().ok())
+            .any(|ip| in_network(ip, network, prefix));
+        if !hit {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|s| !s.is_empty())
+}
+
+fn parse_cidr(value: &str) -> Result<(Ipv4Addr, u8), String> {
+    let (ip, prefix) = match value.split_once('/') {
+        Some((ip, prefix)) => {
+            let prefix: u8 = prefix
+                .trim()
+                .parse()
+                .ok()
+                .filter(|p| *p <= 32)
+                .ok_or_else(|| format!("无效的子网: {}", value))?;
+            (ip, prefix)
+        }
+        None => (value, 32),
+    };
+    let ip = ip
+        .trim()
+        .parse::<Ipv4Addr>()
+        .map_err(|_| format!("无效的子网: {}", value))?;
+    Ok((ip, prefix))
+}
+
+fn in_network(ip: Ipv4Addr, network: Ipv4Addr, prefix: u8) -> bool {
+    let mask = if prefix == 0 { 0 } else { u32::MAX << (32 - prefix) };
+    u32::from(ip) & mask == u32::from(network) & mask
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scenes::NetworkConfig;
+
+    fn scene() -> Scene {
+        let mut scene = Scene {
+            name: "Office A".to_string(),
+            tags: vec!["lab".to_string()],
+            site: Some("Shanghai".to_string()),
+            ..Default::default()
+        };
+        scene.network_configs.insert(
+            "Ethernet".to_string(),
+            NetworkConfig {
+                is_dhcp: false,
+                ip: Some("10.20.30.40".to_string()),
+                subnet: Some("255.255.0.0".to_string()),
+                gateway: None,
+                dns: None,
+            },
+        );
+        scene
+    }
+
+    fn query(text: Option<&str>, tag: Option<&str>, adapter: Option<&str>, subnet: Option<&str>) -> SceneQuery {
+        SceneQuery {
+            text: text.map(String::from),
+            tag: tag.map(String::from),
+            adapter: adapter.map(String::from),
+            subnet: subnet.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_scene_query_filters() {
+        let scene = scene();
+        assert!(matches(&scene, &SceneQuery::default()).unwrap());
+        assert!(matches(&scene, &query(Some("shang"), None, None, None)).unwrap());
+        assert!(matches(&scene, &query(None, Some("LAB"), Some("ethernet"), None)).unwrap());
+        assert!(!matches(&scene, &query(None, Some("prod"), None, None)).unwrap());
+        assert!(!matches(&scene, &query(None, None, Some("Wi-Fi"), None)).unwrap());
+        assert!(matches(&scene, &query(None, None, None, Some("10.20.0.0/16"))).unwrap());
+        assert!(!matches(&scene, &query(None, None, None, Some("10.21.0.0/16"))).unwrap());
+        assert!(matches(&scene, &query(None, None, None, Some("10.20.30.40"))).unwrap());
+        assert!(matches(&scene, &query(None, None, None, Some("bad"))).is_err());
+    }
+}
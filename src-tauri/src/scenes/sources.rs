@@ -0,0 +1,214 @@
+//! 额外的只读场景来源（网络共享目录或本地 git 工作副本）
+//!
+//! 来源中的场景只读展示，需要修改时先“复制到个人目录”（fork）。
+//! 刷新时 git 来源会先执行 `git pull --ff-only`，再根据文件名、大小与修改时间
+//! 计算指纹，用于判断来源内容是否发生变化。
+
+use super::Scene;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+#[cfg(target_os = "windows")]
+use winapi::um::winbase::CREATE_NO_WINDOW;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SceneSourceKind {
+    /// 普通目录（包括 \\server\share 这样的网络共享路径）
+    Folder,
+    /// 本地 git 工作副本，刷新时执行 git pull
+    Git,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SceneSource {
+    /// 来源名称，同时作为场景的 `source` 标记
+    pub name: String,
+    pub kind: SceneSourceKind,
+    pub path: String,
+    #[serde(default)]
+    pub fingerprint: Option<String>,
+    #[serde(default)]
+    pub last_refreshed: Option<u64>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+}
+
+/// 单个来源的刷新结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SceneSourceStatus {
+    pub name: String,
+    pub changed: bool,
+    pub scene_count: usize,
+    pub error: Option<String>,
+}
+
+/// 读取来源目录中的场景，并打上来源标记
+pub fn read_source_scenes(source: &SceneSource) -> Result<Vec<Scene>, String> {
+    let entries = fs::read_dir(&source.path)
+        .map_err(|e| format!("读取场景来源 {} 失败: {}", source.name, e))?;
+
+    let mut scenes = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("json") {
+            continue;
+        }
+        if let Ok(content) = fs::read_to_string(&path) {
+            if let Ok(mut scene) = serde_json::from_str::<Scene>(&content) {
+                scene.source = Some(source.name.clone());
+                scenes.push(scene);
+            }
+        }
+    }
+    scenes.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(scenes)
+}
+
+/// 刷新单个来源：git 来源先拉取，再重新计算指纹
+pub fn refresh_source(source: &mut SceneSource) -> SceneSourceStatus {
+    let result = (|| {
+        if source.kind == SceneSourceKind::Git {
+            git_pull(Path::new(&source.path))?;
+        }
+        let fingerprint = fingerprint_dir(Path::new(&source.path))?;
+        let scene_count = read_source_scenes(source)?.len();
+        Ok::<_, String>((fingerprint, scene_count))
+    })();
+
+    source.last_refreshed = Some(super::now_millis());
+
+    match result {
+        Ok((fingerprint, scene_count)) => {
+            let changed = source.fingerprint.as_deref() != Some(fingerprint.as_str());
+            source.fingerprint = Some(fingerprint);
+            source.last_error = None;
+            SceneSourceStatus {
+                name: source.name.clone(),
+                changed,
+                scene_count,
+                error: None,
+            }
+        }
+        Err(e) => {
+            source.last_error = Some(e.clone());
+            SceneSourceStatus {
+                name: source.name.clone(),
+                changed: false,
+                scene_count: 0,
+                error: Some(e),
+            }
+        }
+    }
+}
+
+/// 根据目录中 json 文件的名称、大小和修改时间计算指纹
+fn fingerprint_dir(dir: &Path) -> Result<String, String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| format!("读取目录 {} 失败: {}", dir.display(), e))?;
+
+    let mut items = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("json") {
+            continue;
+        }
+        let Ok(meta) = entry.metadata() else { continue; };
+        let modified = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        items.push((entry.file_name().to_string_lossy().to_string(), meta.len(), modified));
+    }
+    items.sort();
+
+    // 指纹保存在配置中，需要跨版本稳定
+    let text: String = items
+        .iter()
+        .map(|(name, len, modified)| format!("{}\t{}\t{}\n", name, len, modified))
+        .collect();
+    Ok(format!("{:016x}", crate::storage::stable_hash(text.as_bytes())))
+}
+
+fn git_pull(dir: &Path) -> Result<(), String> {
+    let mut cmd = Command::new("git");
+    cmd.arg("-C").arg(dir).args(["pull", "--ff-only", "--quiet"]);
+
+    // Windows 下避免弹出控制台窗口
+    #[cfg(target_os = "windows")]
+    {
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let output = cmd.output().map_err(|e| format!("执行 git 失败: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git pull 失败: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_scene(dir: &Path, file: &str, name: &str) {
+        let content = format!(r#"{{"name":"{}","network_configs":{{}}}}"#, name);
+        fs::write(dir.join(file), content).unwrap();
+    }
+
+    #[test]
+    fn test_read_and_refresh_folder_source() {
+        let dir = std::env::temp_dir().join(format!("ip-switch-source-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        write_scene(&dir, "office.json", "Office");
+        write_scene(&dir, "home.json", "Home");
+        fs::write(dir.join("broken.json"), "{").unwrap();
+        fs::write(dir.join("notes.txt"), "not a scene").unwrap();
+
+        let mut source = SceneSource {
+            name: "team".to_string(),
+            kind: SceneSourceKind::Folder,
+            path: dir.to_string_lossy().to_string(),
+            fingerprint: None,
+            last_refreshed: None,
+            last_error: None,
+        };
+        let scenes = read_source_scenes(&source).unwrap();
+        assert_eq!(scenes.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(), vec!["Home", "Office"]);
+        assert!(scenes.iter().all(|s| s.source.as_deref() == Some("team")));
+
+        let first = refresh_source(&mut source);
+        assert!(first.changed);
+        assert_eq!(first.scene_count, 2);
+        let fingerprint = source.fingerprint.clone().unwrap();
+        assert_eq!(fingerprint.len(), 16);
+        assert_eq!(fingerprint_dir(&dir).unwrap(), fingerprint);
+
+        // 内容未变化
+        assert!(!refresh_source(&mut source).changed);
+
+        // 只有 json 文件参与指纹
+        fs::write(dir.join("notes.txt"), "changed").unwrap();
+        assert!(!refresh_source(&mut source).changed);
+
+        write_scene(&dir, "lab.json", "Laboratory");
+        let status = refresh_source(&mut source);
+        assert!(status.changed);
+        assert_eq!(status.scene_count, 3);
+
+        fs::remove_dir_all(&dir).unwrap();
+        let status = refresh_source(&mut source);
+        assert!(!status.changed && status.error.is_some());
+        assert_eq!(source.last_error, status.error);
+    }
+}
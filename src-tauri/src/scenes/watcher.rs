@@ -0,0 +1,204 @@
+//! 监听个人场景目录，外部修改/新增/删除场景文件时通知前端
+//!
+//! 文件系统事件经过去抖后，与上一次已知的目录状态比较，重新校验变化的文件，
+//! 然后发送 `scene-added` / `scene-changed` / `scene-removed` / `scene-invalid` 事件。
+
+use super::Scene;
+use notify_debouncer_mini::notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::Emitter;
+
+/// 去抖时间：编辑器保存文件时通常会触发多次写入
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// 保存在应用状态中，保证监听器在应用运行期间不被释放
+pub struct SceneWatcher(#[allow(dead_code)] Mutex<Debouncer<RecommendedWatcher>>);
+
+/// 发送给前端的事件内容
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SceneFileEvent {
+    pub file_name: String,
+    pub scene_name: Option<String>,
+    pub error: Option<String>,
+}
+
+/// 目录中每个场景文件的最近状态
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum FileState {
+    Valid { scene_name: String, hash: u64 },
+    Invalid { hash: u64 },
+}
+
+#[derive(Debug, Default)]
+struct SceneDirState {
+    files: HashMap<PathBuf, FileState>,
+}
+
+impl SceneDirState {
+    fn scan(dir: &Path) -> Self {
+        let mut state = Self::default();
+        if let Ok(entries) = fs::read_dir(dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if is_scene_file(&path) {
+                    if let Some(file_state) = read_state(&path).0 {
+                        state.files.insert(path, file_state);
+                    }
+                }
+            }
+        }
+        state
+    }
+
+    /// 根据文件当前状态更新记录，返回需要发送的事件
+    fn update(&mut self, path: &Path) -> Option<(&'static str, SceneFileEvent)> {
+        if !is_scene_file(path) {
+            return None;
+        }
+        let file_name = path.file_name()?.to_string_lossy().to_string();
+        let (current, error) = read_state(path);
+        let previous = match &current {
+            Some(state) => self.files.insert(path.to_path_buf(), state.clone()),
+            None => self.files.remove(path),
+        };
+
+        match (previous, current) {
+            (None, None) => None,
+            (Some(prev), None) => Some((
+                "scene-removed",
+                SceneFileEvent {
+                    file_name,
+                    scene_name: match prev {
+                        FileState::Valid { scene_name, .. } => Some(scene_name),
+                        FileState::Invalid { .. } => None,
+                    },
+                    error: None,
+                },
+            )),
+            // 仍然是同一个无效内容时不重复通知
+            (Some(FileState::Invalid { hash: old_hash }), Some(FileState::Invalid { hash })) if old_hash == hash => None,
+            (_, Some(FileState::Invalid { .. })) => Some((
+                "scene-invalid",
+                SceneFileEvent {
+                    file_name,
+                    scene_name: None,
+                    error,
+                },
+            )),
+            (prev, Some(FileState::Valid { scene_name, hash })) => {
+                let event = match prev {
+                    None | Some(FileState::Invalid { .. }) => "scene-added",
+                    Some(FileState::Valid { hash: old_hash, .. }) if old_hash == hash => return None,
+                    Some(FileState::Valid { .. }) => "scene-changed",
+                };
+                Some((
+                    event,
+                    SceneFileEvent {
+                        file_name,
+                        scene_name: Some(scene_name),
+                        error: None,
+                    },
+                ))
+            }
+        }
+    }
+}
+
+/// 开始监听场景目录
+pub fn start(app: tauri::AppHandle, dir: PathBuf) -> Result<SceneWatcher, String> {
+    let state = Mutex::new(SceneDirState::scan(&dir));
+
+    let mut debouncer = new_debouncer(DEBOUNCE, move |res: DebounceEventResult| match res {
+        Ok(events) => {
+            let Ok(mut state) = state.lock() else { return; };
+            for event in events {
+                if let Some((name, payload)) = state.update(&event.path) {
+                    let _ = app.emit(name, payload);
+                }
+            }
+        }
+        Err(e) => eprintln!("场景目录监听出错: {}", e),
+    })
+    .map_err(|e| format!("创建场景目录监听失败: {}", e))?;
+
+    debouncer
+        .watcher()
+        .watch(&dir, RecursiveMode::NonRecursive)
+        .map_err(|e| format!("监听场景目录失败: {}", e))?;
+
+    Ok(SceneWatcher(Mutex::new(debouncer)))
+}
+
+/// 只关心场景 json 文件，忽略备份文件与临时文件
+fn is_scene_file(path: &Path) -> bool {
+    let is_json = path.extension().and_then(|s| s.to_str()) == Some("json");
+    let is_backup = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .map(|s| s.starts_with("_backup_"))
+        .unwrap_or(false);
+    is_json && !is_backup
+}
+
+/// 读取并校验文件，文件不存在时返回 None
+fn read_state(path: &Path) -> (Option<FileState>, Option<String>) {
+    let Ok(content) = fs::read(path) else {
+        return (None, None);
+    };
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    let hash = hasher.finish();
+
+    match serde_json::from_slice::<Scene>(&content) {
+        Ok(scene) => (
+            Some(FileState::Valid {
+                scene_name: scene.name,
+                hash,
+            }),
+            None,
+        ),
+        Err(e) => (Some(FileState::Invalid { hash }), Some(e.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scene_dir_state_events() {
+        let dir = std::env::temp_dir().join(format!("ip-switch-watcher-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let mut state = SceneDirState::scan(&dir);
+
+        let file = dir.join("office.json");
+        let scene = r#"{"name":"office","network_configs":{},"hosts_content":null,"proxy_config":null}"#;
+        fs::write(&file, scene).unwrap();
+        assert_eq!(state.update(&file).unwrap().0, "scene-added");
+        // 内容未变化时不重复通知
+        assert!(state.update(&file).is_none());
+
+        fs::write(&file, scene.replace("null}", "null,\"tray_color\":\"#FF0000\"}")).unwrap();
+        assert_eq!(state.update(&file).unwrap().0, "scene-changed");
+
+        fs::write(&file, "{ broken").unwrap();
+        let (name, event) = state.update(&file).unwrap();
+        assert_eq!(name, "scene-invalid");
+        assert!(event.error.is_some());
+
+        fs::remove_file(&file).unwrap();
+        assert_eq!(state.update(&file).unwrap().0, "scene-removed");
+
+        assert!(state.update(&dir.join("_backup_before_scene.json")).is_none());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
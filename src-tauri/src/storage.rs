@@ -0,0 +1,52 @@
+//! 应用私有数据（app data 目录）的读写工具
+//!
+//! 场景保存在用户文档目录中，而密钥、订阅、配置档案等由应用自身维护的数据
+//! 统一放在 app data 目录下，以 JSON 文件保存。
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+/// 获取（并创建）应用数据目录
+pub fn app_data_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("获取应用数据目录失败: {}", e))?;
+    fs::create_dir_all(&dir).map_err(|e| format!("创建应用数据目录失败: {}", e))?;
+    Ok(dir)
+}
+
+/// 应用数据目录下的文件路径
+pub fn app_data_file(app: &tauri::AppHandle, name: &str) -> Result<PathBuf, String> {
+    Ok(app_data_dir(app)?.join(name))
+}
+
+/// 64 位 FNV-1a 哈希。用于保存到磁盘的文件名、指纹等，结果不随编译器版本变化
+/// （标准库的 `DefaultHasher` 不保证这一点）
+pub fn stable_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// 读取 JSON 文件，文件不存在时返回默认值
+pub fn load_json<T: DeserializeOwned + Default>(path: &Path) -> Result<T, String> {
+    if !path.exists() {
+        return Ok(T::default());
+    }
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("读取 {} 失败: {}", path.display(), e))?;
+    serde_json::from_str(&content).map_err(|e| format!("解析 {} 失败: {}", path.display(), e))
+}
+
+/// 写入 JSON 文件（先写临时文件再替换，避免写一半时崩溃导致文件损坏）
+pub fn save_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("序列化 {} 失败: {}", path.display(), e))?;
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, content).map_err(|e| format!("写入 {} 失败: {}", path.display(), e))?;
+    fs::rename(&tmp, path).map_err(|e| format!("写入 {} 失败: {}", path.display(), e))
+}
@@ -7,10 +7,8 @@ import { t } from './i18n.js';
 // 默认远程hosts URL
 const DEFAULT_HOSTS_URL = 'https://gitlab.com/ineo6/hosts/-/raw/master/next-hosts';
 
-// 定时更新相关变量
-let scheduledUpdateInterval = null;
-let scheduledUpdateCheckInterval = null;
-let lastUpdateHour = null; // 记录上次更新的小时，避免重复更新
+// 编辑器中的远程URL对应的后端订阅名称
+const REMOTE_SUBSCRIPTION_NAME = 'remote';
 
 // 编辑Hosts - 使用文本编辑器界面
 export async function editHosts() {
@@ -30,9 +28,6 @@ function showHostsEditor(content) {
   // 从localStorage获取保存的远程URL，如果没有则使用默认值
   const savedUrl = localStorage.getItem('hostsRemoteUrl') || DEFAULT_HOSTS_URL;
   
-  // 从localStorage获取定时更新配置
-  const scheduledUpdateConfig = JSON.parse(localStorage.getItem('hostsScheduledUpdate') || '{"enabled": false}');
-  
   modal.innerHTML = `
     <div class="modal-content hosts-modal">
       <div class="modal-header">
@@ -47,6 +42,14 @@ function showHostsEditor(content) {
                    value="${escapeHtml(savedUrl)}" 
                    placeholder="${escapeHtml(DEFAULT_HOSTS_URL)}"
                    style="flex: 1; min-width: 300px;">
+            <select id="hosts-format-select" class="form-input" style="width: auto; margin-left: 8px;" title="${t('hostsSourceFormat')}">
+              <option value="hosts">Hosts</option>
+              <option value="auto">${t('hostsFormatAuto')}</option>
+              <option value="adblock">Adblock</option>
+              <option value="dnsmasq">dnsmasq</option>
+              <option value="unbound">unbound</option>
+              <option value="domains">${t('hostsFormatDomains')}</option>
+            </select>
             <button id="hosts-update-btn" class="btn btn-sm btn-ghost" onclick="window.updateHostsFromRemote()">
               <img class="btn-icon" src="/imgs/svg/common/refresh.svg" alt="" />
               ${t('update')}
@@ -54,7 +57,7 @@ function showHostsEditor(content) {
           </div>
           <div class="hosts-scheduled-update-group" style="margin-top: 8px;">
             <label class="checkbox-label">
-              <input type="checkbox" id="hosts-scheduled-update-checkbox" ${scheduledUpdateConfig.enabled ? 'checked' : ''}>
+              <input type="checkbox" id="hosts-scheduled-update-checkbox">
               <span>${t('scheduledUpdate')}</span>
             </label>
           </div>
@@ -62,6 +65,8 @@ function showHostsEditor(content) {
         <textarea id="hosts-editor" class="hosts-editor" spellcheck="false">${escapeHtml(content)}</textarea>
       </div>
       <div class="modal-footer">
+        <button class="btn btn-ghost" onclick="window.lintHosts()">${t('lintHosts')}</button>
+        <button class="btn btn-ghost" onclick="window.normalizeHosts()">${t('normalizeHosts')}</button>
         <button class="btn btn-secondary" onclick="this.closest('.modal-overlay').remove()">${t('cancel')}</button>
         <button class="btn btn-primary" onclick="window.saveHosts()">${t('save')}</button>
       </div>
@@ -80,18 +85,28 @@ function showHostsEditor(content) {
     });
   }
   
-  // 监听定时更新复选框变化
+  // 定时更新由后端订阅负责：勾选即启用订阅，取消勾选则停用并从Hosts中移除其内容
   const scheduledUpdateCheckbox = document.getElementById('hosts-scheduled-update-checkbox');
   if (scheduledUpdateCheckbox) {
-    scheduledUpdateCheckbox.addEventListener('change', (e) => {
-      const enabled = e.target.checked;
-      const config = { enabled };
-      localStorage.setItem('hostsScheduledUpdate', JSON.stringify(config));
-      
-      if (enabled) {
-        startScheduledUpdate();
-      } else {
-        stopScheduledUpdate();
+    getRemoteSubscription().then(sub => {
+      scheduledUpdateCheckbox.checked = !!sub?.enabled;
+      const formatSelect = document.getElementById('hosts-format-select');
+      if (formatSelect && sub?.format) formatSelect.value = sub.format;
+    });
+    scheduledUpdateCheckbox.addEventListener('change', async (e) => {
+      const url = urlInput?.value.trim() || savedUrl;
+      try {
+        await invoke('save_hosts_subscription', {
+          name: REMOTE_SUBSCRIPTION_NAME,
+          url,
+          intervalMinutes: null,
+          enabled: e.target.checked,
+          format: getSelectedFormat(),
+        });
+        await reloadHostsEditor();
+      } catch (error) {
+        e.target.checked = !e.target.checked;
+        alert(t('remoteUpdateFailed', { error }));
       }
     });
   }
@@ -139,44 +154,45 @@ window.updateHostsFromRemote = async function() {
   updateBtn.innerHTML = `<span>${t('updating')}</span>`;
   
   try {
-    // 从远程获取hosts内容（通过后端）
-    const remoteContent = await invoke('fetch_remote_hosts', { url });
-    
-    if (!remoteContent || !remoteContent.trim()) {
-      alert(t('remoteContentEmpty'));
-      updateBtn.disabled = false;
-      updateBtn.innerHTML = originalText;
-      return;
+    // 已开启定时更新时刷新后端订阅（保留其启用状态与间隔），受管区块中的上一版本整体替换；
+    // 否则只下载一次，替换编辑器中上一次下载的内容，由用户确认后保存
+    const existing = await getRemoteSubscription();
+    let report;
+    if (existing?.enabled) {
+      await invoke('save_hosts_subscription', {
+        name: REMOTE_SUBSCRIPTION_NAME,
+        url,
+        intervalMinutes: null,
+        enabled: null,
+        format: getSelectedFormat(),
+      });
+      const subs = await invoke('refresh_hosts_subscriptions', { name: REMOTE_SUBSCRIPTION_NAME });
+      const sub = subs.find(s => s.name === REMOTE_SUBSCRIPTION_NAME);
+      if (sub?.last_error) {
+        throw sub.last_error;
+      }
+      await reloadHostsEditor();
+      report = sub?.report;
+    } else {
+      const fetched = await invoke('fetch_remote_hosts', { url, format: getSelectedFormat() });
+      if (!fetched.content.trim()) {
+        alert(t('remoteContentEmpty'));
+        return;
+      }
+      editor.value = replaceRemoteSection(editor.value, fetched.content);
+      editor.scrollTop = editor.scrollHeight;
+      report = fetched.report;
     }
     
-    // 获取当前编辑器内容
-    const currentContent = editor.value;
-    
-    // 统计远程内容中的有效记录数（非注释行）
-    const remoteLines = remoteContent.split('\n').filter(line => {
-      const trimmed = line.trim();
-      return trimmed && !trimmed.startsWith('#');
-    });
-    
-    // 追加远程内容到编辑器（如果不存在）
-    let newContent = currentContent;
-    if (currentContent && !currentContent.endsWith('\n')) {
-      newContent += '\n';
+    let message = t('hostsUpdatedOk');
+    if (report?.rejected_count > 0) {
+      const lines = report.rejected
+        .slice(0, 10)
+        .map(r => `#${r.line}: ${r.text} (${r.reason})`)
+        .join('\n');
+      message += '\n\n' + t('hostsLinesRejected', { count: report.rejected_count, lines });
     }
-    
-    // 添加分隔注释和远程内容
-    const separator = `\n# ===== 远程更新内容 (${new Date().toLocaleString('zh-CN')}) =====\n`;
-    newContent += separator;
-    newContent += remoteContent;
-    newContent += '\n# ===== 远程更新内容结束 =====\n';
-    
-    // 更新编辑器内容
-    editor.value = newContent;
-    
-    // 滚动到底部显示新添加的内容
-    editor.scrollTop = editor.scrollHeight;
-    
-    alert(t('hostsUpdatedOk'));
+    alert(message);
   } catch (error) {
     console.error('从远程更新hosts失败:', error);
     const errorMsg = typeof error === 'string' ? error : error.message || String(error);
@@ -187,6 +203,22 @@ window.updateHostsFromRemote = async function() {
   }
 };
 
+// 编辑器中单次下载的内容所在的段落
+const REMOTE_SECTION_BEGIN = '# ===== 远程更新内容 =====';
+const REMOTE_SECTION_END = '# ===== 远程更新内容结束 =====';
+
+// 用新下载的内容替换上一次的段落（没有时追加到末尾）
+function replaceRemoteSection(content, remote) {
+  const section = `${REMOTE_SECTION_BEGIN}\n${remote.trimEnd()}\n${REMOTE_SECTION_END}\n`;
+  const begin = content.indexOf(REMOTE_SECTION_BEGIN);
+  const end = content.indexOf(REMOTE_SECTION_END, begin);
+  if (begin !== -1 && end !== -1) {
+    return content.slice(0, begin) + section + content.slice(end + REMOTE_SECTION_END.length).replace(/^\n/, '');
+  }
+  const separator = content && !content.endsWith('\n') ? '\n\n' : '\n';
+  return content ? content + separator + section : section;
+}
+
 // 保存Hosts
 window.saveHosts = async function() {
   const editor = document.getElementById('hosts-editor');
@@ -201,142 +233,89 @@ window.saveHosts = async function() {
   }
 };
 
-// 定时更新hosts（静默更新，不打开编辑器）
-async function performScheduledUpdate() {
+// 格式化检查结果（行号从0开始，显示时加1）
+function formatLintIssues(issues) {
+  return issues
+    .slice(0, 20)
+    .map(issue => `#${issue.line + 1}: ${issue.message}`)
+    .join('\n');
+}
+
+// 检查编辑器中的Hosts内容
+window.lintHosts = async function() {
+  const editor = document.getElementById('hosts-editor');
+  if (!editor) return;
+  
   try {
-    const savedUrl = localStorage.getItem('hostsRemoteUrl') || DEFAULT_HOSTS_URL;
-    if (!savedUrl) {
-      console.warn('自动更新hosts: 未配置远程URL');
-      return;
-    }
-    
-    // 从远程获取内容
-    const remoteContent = await invoke('fetch_remote_hosts', { url: savedUrl });
-    
-    if (!remoteContent || !remoteContent.trim()) {
-      console.warn('自动更新hosts: 远程内容为空');
-      return;
-    }
-    
-    // 获取当前hosts内容
-    const currentContent = await invoke('get_hosts');
-    
-    // 合并内容
-    let newContent = currentContent;
-    if (currentContent && !currentContent.endsWith('\n')) {
-      newContent += '\n';
+    const issues = await invoke('lint_hosts', { content: editor.value });
+    if (issues.length === 0) {
+      alert(t('hostsLintClean'));
+    } else {
+      alert(t('hostsLintIssues', { count: issues.length, issues: formatLintIssues(issues) }));
     }
-    
-    // 添加分隔注释和远程内容
-    const separator = `\n# ===== 定时更新内容 (${new Date().toLocaleString('zh-CN')}) =====\n`;
-    newContent += separator;
-    newContent += remoteContent;
-    newContent += '\n# ===== 定时更新内容结束 =====\n';
-    
-    // 保存到hosts文件
-    await invoke('set_hosts', { content: newContent });
-    
-    console.log('定时更新hosts成功:', new Date().toLocaleString('zh-CN'));
   } catch (error) {
-    console.error('定时更新hosts失败:', error);
+    alert(t('hostsUpdateFailed', { error }));
   }
-}
+};
 
-// 计算到下一个更新时间的毫秒数
-function getNextUpdateTime() {
-  const now = new Date();
-  const hours = now.getHours();
-  const minutes = now.getMinutes();
-  const seconds = now.getSeconds();
-  
-  // 更新时间为9:00和17:00
-  const updateTimes = [9, 17];
-  
-  // 找到下一个更新时间
-  let nextHour = null;
-  for (const updateHour of updateTimes) {
-    if (hours < updateHour || (hours === updateHour && minutes === 0 && seconds === 0)) {
-      nextHour = updateHour;
-      break;
+// 一键整理Hosts文件（去重、组内排序），整理的是已保存的文件
+window.normalizeHosts = async function() {
+  try {
+    const report = await invoke('normalize_hosts');
+    await reloadHostsEditor();
+    let message = t('hostsNormalized', { count: report.removed_mappings });
+    if (report.remaining_issues.length > 0) {
+      message += '\n\n' + t('hostsLintIssues', {
+        count: report.remaining_issues.length,
+        issues: formatLintIssues(report.remaining_issues),
+      });
     }
+    alert(message);
+  } catch (error) {
+    alert(t('hostsUpdateFailed', { error }));
   }
-  
-  // 如果今天没有更新了，使用明天的第一个更新时间
-  if (nextHour === null) {
-    nextHour = updateTimes[0];
-    now.setDate(now.getDate() + 1);
-  }
-  
-  // 设置到更新时间
-  now.setHours(nextHour, 0, 0, 0);
-  
-  return now.getTime() - Date.now();
+};
+
+// 编辑器中选择的远程内容格式
+function getSelectedFormat() {
+  return document.getElementById('hosts-format-select')?.value || 'hosts';
 }
 
-// 启动定时更新
-export function startScheduledUpdate() {
-  // 先停止现有的定时器
-  stopScheduledUpdate();
-  
-  // 重置上次更新时间
-  lastUpdateHour = null;
-  
-  // 立即检查是否需要更新
-  const now = new Date();
-  const hours = now.getHours();
-  const minutes = now.getMinutes();
-  
-  // 如果当前时间是9:00或17:00，立即执行一次
-  if ((hours === 9 || hours === 17) && minutes === 0) {
-    setTimeout(() => {
-      performScheduledUpdate();
-      lastUpdateHour = hours;
-    }, 2000); // 延迟2秒执行，避免立即触发
+// 获取编辑器对应的后端订阅
+async function getRemoteSubscription() {
+  try {
+    const subs = await invoke('list_hosts_subscriptions');
+    return subs.find(s => s.name === REMOTE_SUBSCRIPTION_NAME) || null;
+  } catch (error) {
+    console.error('获取Hosts订阅失败:', error);
+    return null;
   }
-  
-  // 计算到下一个更新的时间
-  const delay = getNextUpdateTime();
-  
-  // 设置定时器，在下一个更新时间执行
-  scheduledUpdateInterval = setTimeout(() => {
-    performScheduledUpdate();
-    lastUpdateHour = new Date().getHours();
-    
-    // 执行后，设置每天检查的定时器
-    scheduledUpdateCheckInterval = setInterval(() => {
-      const now = new Date();
-      const hours = now.getHours();
-      const minutes = now.getMinutes();
-      
-      // 在9:00和17:00的整点执行更新（避免重复）
-      if ((hours === 9 || hours === 17) && minutes === 0 && lastUpdateHour !== hours) {
-        performScheduledUpdate();
-        lastUpdateHour = hours;
-      }
-    }, 60000); // 每分钟检查一次
-  }, delay);
-  
-  const nextUpdateTime = new Date(Date.now() + delay);
-  console.log('Hosts定时更新已启动，下次更新:', nextUpdateTime.toLocaleString('zh-CN'));
 }
 
-// 停止定时更新
-export function stopScheduledUpdate() {
-  if (scheduledUpdateInterval) {
-    clearTimeout(scheduledUpdateInterval);
-    scheduledUpdateInterval = null;
-  }
-  if (scheduledUpdateCheckInterval) {
-    clearInterval(scheduledUpdateCheckInterval);
-    scheduledUpdateCheckInterval = null;
-  }
-  console.log('Hosts定时更新已停止');
+// 重新读取Hosts文件到编辑器
+async function reloadHostsEditor() {
+  const editor = document.getElementById('hosts-editor');
+  if (!editor) return;
+  editor.value = await invoke('get_hosts');
 }
 
-// 初始化定时更新（应用启动时调用）
-export function initHostsScheduledUpdate() {
-  const scheduledUpdateConfig = JSON.parse(localStorage.getItem('hostsScheduledUpdate') || '{"enabled": false}');
-  if (scheduledUpdateConfig.enabled) {
-    startScheduledUpdate();
+// 迁移旧版前端定时更新配置（定时更新现由后端订阅负责）
+export async function initHostsScheduledUpdate() {
+  const legacyConfig = JSON.parse(localStorage.getItem('hostsScheduledUpdate') || '{"enabled": false}');
+  if (!legacyConfig.enabled) return;
+  
+  try {
+    if (!(await getRemoteSubscription())) {
+      const url = localStorage.getItem('hostsRemoteUrl') || DEFAULT_HOSTS_URL;
+      await invoke('save_hosts_subscription', {
+        name: REMOTE_SUBSCRIPTION_NAME,
+        url,
+        intervalMinutes: null,
+        enabled: true,
+      });
+    }
+    localStorage.removeItem('hostsScheduledUpdate');
+  } catch (error) {
+    console.error('迁移Hosts定时更新配置失败:', error);
   }
 }
@@ -78,7 +78,10 @@ const DICT = {
     hostsEditorTitle: '编辑 Hosts 文件',
     remoteUrl: '远程URL:',
     update: '更新',
-    scheduledUpdate: '定时更新（每天9:00、17:00）',
+    scheduledUpdate: '订阅并定时更新（每12小时）',
+    hostsSourceFormat: '远程内容格式',
+    hostsFormatAuto: '自动识别',
+    hostsFormatDomains: '域名列表',
     loadHostsFailed: '加载Hosts文件失败: {error}',
     enterRemoteUrl: '请输入远程URL',
 
@@ -91,11 +94,67 @@ const DICT = {
     proxyUseScript: '使用设置脚本',
     proxyScriptAddress: '脚本地址:',
     proxyServerHint: '格式: IP:端口 或 域名:端口',
-    proxyBypassHint: '多个地址用分号(;)分隔，例如: localhost;127.0.0.1;*.local',
+    proxyPerProtocol: '按协议分别设置（HTTP/HTTPS/FTP/SOCKS）',
+    proxyBypassHint: '多个地址用分号(;)分隔，支持通配符、IP、CIDR 网段和 <local>，例如: localhost;10.0.0.0/8;*.local',
+    bypassPresetPrivate: '添加私有地址',
+    bypassPresetCorporate: '添加内网域名',
+    bypassCheck: '校验',
+    bypassValid: '绕过列表有效',
+    bypassValidExpanded: '绕过列表有效，以下项写入时会展开:\n{entries}',
+    bypassInvalid: '绕过列表中有无效项:\n{errors}',
+    proxyChangedExternally: '系统代理设置已被其他程序修改，请重新打开编辑器查看最新设置',
+    proxyProfile: '代理方案:',
+    proxyProfileDirect: '直连（内置）',
+    proxyProfileApply: '应用',
+    proxyProfileSave: '另存为方案',
+    proxyProfileDelete: '删除',
+    proxyProfileNamePrompt: '方案名称（同名会覆盖）:',
+    proxyProfileDeleteConfirm: '确定删除代理方案 {name} 吗？',
+    proxyProfileFailed: '代理方案操作失败: {error}',
+    proxyCredentialUsername: '认证用户名（可选）',
+    proxyCredentialPassword: '认证密码',
+    proxyCredentialSaved: '已保存（留空则不修改）',
+    proxyCredentialHint: '认证信息随方案保存到系统凭据库，不会写入系统代理设置或导出的场景；地址中的 user:pass@ 也会移到凭据库',
     loadProxyFailed: '加载代理配置失败: {error}',
     proxyServerRequired: '启用代理时必须填写代理服务器地址',
     proxyUpdated: '代理配置已更新',
     updateProxyFailed: '更新代理失败: {error}',
+    proxyPropagation: '同步到:',
+    proxyPropagationHint: '关闭代理或取消勾选时恢复原设置',
+    propagationEnv: '环境变量',
+    propagationGit: 'git',
+    propagationNpm: 'npm',
+    propagationPnpm: 'pnpm',
+    propagationPip: 'pip',
+    propagationWinHttp: 'WinHTTP',
+    proxyPropagationFailed: '部分目标同步失败:\n{errors}',
+    proxyPropagationCredential: '同步时包含认证信息:',
+    proxyPropagationCredentialHint: '默认不同步认证信息。勾选后用户名和密码会以明文写入对应工具的配置文件；环境变量和 WinHTTP 不会包含认证信息',
+    proxyPropagationCredentialConfirm: '代理认证的用户名和密码将以明文写入 {targets} 的配置文件，确定继续吗？',
+    proxyTest: '测试代理',
+    proxyTestConnect: '连接 {proxy} 耗时: {ms} ms',
+    proxyTestStatus: '响应状态: {status}',
+    proxyTestAuthRequired: '代理需要认证: {schemes}',
+    proxyTestEgressIp: '出口 IP: {ip}',
+    proxyTestError: '测试失败: {error}',
+    pacServer: '本机 PAC 服务',
+    pacEnabled: '启用本机 PAC 服务并设为系统自动配置脚本',
+    pacPort: '监听端口:',
+    pacMode: '脚本来源:',
+    pacModeGenerated: '按规则生成',
+    pacModeCustom: '自定义脚本',
+    pacRules: '代理规则:',
+    pacRulesHint: '每行一条：主机或通配符，空格后为代理，例如: *.example.com 127.0.0.1:7890 或 *.example.com SOCKS5 127.0.0.1:1080',
+    pacDefaultProxy: '其他请求使用的代理（留空则直连）:',
+    pacScript: 'PAC 脚本:',
+    pacPreview: '预览脚本',
+    pacSaved: 'PAC 设置已保存',
+    pacSavedWithUrl: 'PAC 设置已保存，系统自动配置脚本地址: {url}',
+    pacSaveFailed: '保存 PAC 设置失败: {error}',
+    pacEvaluate: '测试地址',
+    pacEvaluatePrompt: '输入要测试的网址或主机名（使用系统当前的自动配置脚本）:',
+    pacEvaluateResult: '{host} 的结果: {result}\n\n{chain}',
+    pacEvaluateFailed: '执行 PAC 脚本失败: {error}',
 
     // 网络配置
     configureNetwork: '配置网络',
@@ -178,6 +237,7 @@ const DICT = {
     exportScenesOk: '场景配置已导出',
     exportScenesFailed: '导出场景配置失败: {error}',
     importScenesOk: '场景配置已导入',
+    importScenesOkPublisher: '场景配置已导入（发布者: {publisher}）',
     importScenesFailed: '导入场景配置失败: {error}',
     deleteSceneFailed: '删除场景失败: {error}',
     clearScenesOk: '所有场景已清空',
@@ -188,6 +248,12 @@ const DICT = {
     remoteContentEmpty: '远程内容为空',
     remoteUpdateFailed: '从远程更新hosts失败: {error}\n\n请检查：\n1. 网络连接是否正常\n2. URL是否正确\n3. 服务器是否可访问',
     hostsUpdatedOk: 'Hosts文件已更新',
+    lintHosts: '检查',
+    normalizeHosts: '整理',
+    hostsLintClean: '未发现问题',
+    hostsLintIssues: '发现 {count} 个问题：\n{issues}',
+    hostsNormalized: '整理完成，已删除 {count} 条重复映射',
+    hostsLinesRejected: '有 {count} 行远程内容未通过校验，已被忽略：\n{lines}',
     hostsUpdateFailed: '更新Hosts失败: {error}',
     updating: '更新中...',
   },
@@ -264,7 +330,10 @@ const DICT = {
     hostsEditorTitle: 'Edit Hosts File',
     remoteUrl: 'Remote URL:',
     update: 'Update',
-    scheduledUpdate: 'Scheduled update (daily 09:00, 17:00)',
+    scheduledUpdate: 'Subscribe and update automatically (every 12 hours)',
+    hostsSourceFormat: 'Remote content format',
+    hostsFormatAuto: 'Auto detect',
+    hostsFormatDomains: 'Domain list',
     loadHostsFailed: 'Failed to load hosts file: {error}',
     enterRemoteUrl: 'Please enter a remote URL.',
 
@@ -277,11 +346,67 @@ const DICT = {
     proxyUseScript: 'Use setup script',
     proxyScriptAddress: 'Script address:',
     proxyServerHint: 'Format: IP:port or domain:port',
-    proxyBypassHint: 'Separate entries with semicolons (;), e.g. localhost;127.0.0.1;*.local',
+    proxyPerProtocol: 'Set per protocol (HTTP/HTTPS/FTP/SOCKS)',
+    proxyBypassHint: 'Separate entries with semicolons (;). Wildcards, IPs, CIDR ranges and <local> are supported, e.g. localhost;10.0.0.0/8;*.local',
+    bypassPresetPrivate: 'Add private ranges',
+    bypassPresetCorporate: 'Add intranet domains',
+    bypassCheck: 'Validate',
+    bypassValid: 'The bypass list is valid',
+    bypassValidExpanded: 'The bypass list is valid. These entries are expanded when saved:\n{entries}',
+    bypassInvalid: 'The bypass list has invalid entries:\n{errors}',
+    proxyChangedExternally: 'The system proxy was changed by another program. Reopen the editor to see the latest settings.',
+    proxyProfile: 'Proxy profile:',
+    proxyProfileDirect: 'Direct (built-in)',
+    proxyProfileApply: 'Apply',
+    proxyProfileSave: 'Save as profile',
+    proxyProfileDelete: 'Delete',
+    proxyProfileNamePrompt: 'Profile name (an existing profile with the same name is replaced):',
+    proxyProfileDeleteConfirm: 'Delete proxy profile {name}?',
+    proxyProfileFailed: 'Proxy profile operation failed: {error}',
+    proxyCredentialUsername: 'Auth username (optional)',
+    proxyCredentialPassword: 'Auth password',
+    proxyCredentialSaved: 'Saved (leave empty to keep)',
+    proxyCredentialHint: 'Credentials are saved with the profile in the system credential vault, never in the system proxy settings or scene exports. A user:pass@ prefix in the address is moved to the vault too.',
     loadProxyFailed: 'Failed to load proxy settings: {error}',
     proxyServerRequired: 'Proxy server is required when proxy is enabled.',
     proxyUpdated: 'Proxy settings updated.',
     updateProxyFailed: 'Failed to update proxy: {error}',
+    proxyPropagation: 'Also apply to:',
+    proxyPropagationHint: 'Previous settings are restored when the proxy is turned off or a target is unchecked.',
+    propagationEnv: 'Environment variables',
+    propagationGit: 'git',
+    propagationNpm: 'npm',
+    propagationPnpm: 'pnpm',
+    propagationPip: 'pip',
+    propagationWinHttp: 'WinHTTP',
+    proxyPropagationFailed: 'Some targets could not be updated:\n{errors}',
+    proxyPropagationCredential: 'Include credentials for:',
+    proxyPropagationCredentialHint: 'Credentials are not propagated by default. When checked, the username and password are written in plain text to that tool\'s config file. Environment variables and WinHTTP never receive credentials.',
+    proxyPropagationCredentialConfirm: 'The proxy username and password will be written in plain text to the config files of {targets}. Continue?',
+    proxyTest: 'Test proxy',
+    proxyTestConnect: 'Connected to {proxy} in {ms} ms',
+    proxyTestStatus: 'Response status: {status}',
+    proxyTestAuthRequired: 'Proxy requires authentication: {schemes}',
+    proxyTestEgressIp: 'Egress IP: {ip}',
+    proxyTestError: 'Test failed: {error}',
+    pacServer: 'Local PAC server',
+    pacEnabled: 'Serve PAC locally and use it as the system setup script',
+    pacPort: 'Listen port:',
+    pacMode: 'Script source:',
+    pacModeGenerated: 'Generate from rules',
+    pacModeCustom: 'Custom script',
+    pacRules: 'Proxy rules:',
+    pacRulesHint: 'One per line: host or wildcard, then the proxy, e.g. *.example.com 127.0.0.1:7890 or *.example.com SOCKS5 127.0.0.1:1080',
+    pacDefaultProxy: 'Proxy for other requests (empty for direct):',
+    pacScript: 'PAC script:',
+    pacPreview: 'Preview script',
+    pacSaved: 'PAC settings saved.',
+    pacSavedWithUrl: 'PAC settings saved. System setup script address: {url}',
+    pacSaveFailed: 'Failed to save PAC settings: {error}',
+    pacEvaluate: 'Test URL',
+    pacEvaluatePrompt: 'Enter a URL or host name to test (uses the current system setup script):',
+    pacEvaluateResult: 'Result for {host}: {result}\n\n{chain}',
+    pacEvaluateFailed: 'Failed to evaluate PAC script: {error}',
 
     // Network config
     configureNetwork: 'Configure',
@@ -364,6 +489,7 @@ const DICT = {
     exportScenesOk: 'Scene configuration exported.',
     exportScenesFailed: 'Failed to export scene configuration: {error}',
     importScenesOk: 'Scene configuration imported.',
+    importScenesOkPublisher: 'Scene configuration imported (publisher: {publisher}).',
     importScenesFailed: 'Failed to import scene configuration: {error}',
     deleteSceneFailed: 'Failed to delete scene: {error}',
     clearScenesOk: 'All scenes cleared.',
@@ -374,6 +500,12 @@ const DICT = {
     remoteContentEmpty: 'Remote content is empty.',
     remoteUpdateFailed: 'Failed to update hosts from remote: {error}\n\nPlease check:\n1. Network connection\n2. URL correctness\n3. Server availability',
     hostsUpdatedOk: 'Hosts file updated.',
+    lintHosts: 'Check',
+    normalizeHosts: 'Normalize',
+    hostsLintClean: 'No problems found.',
+    hostsLintIssues: '{count} problems found:\n{issues}',
+    hostsNormalized: 'Normalized. Removed {count} duplicate mappings.',
+    hostsLinesRejected: '{count} remote lines failed validation and were skipped:\n{lines}',
     hostsUpdateFailed: 'Failed to update hosts file: {error}',
     updating: 'Updating...',
   }
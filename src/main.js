@@ -5,9 +5,9 @@ import NetworkStatus from './network-status.js';
 import { initTheme, toggleTheme } from './theme.js';
 import * as state from './state.js';
 import { refreshNetworkInfo, renderNetworkInfo, initNetworkFilter, startFastMediaStateWatcher } from './network.js';
-import { loadScenes, renderScenes } from './scenes.js';
+import { loadScenes, renderScenes, watchSceneFiles } from './scenes.js';
 import { editHosts } from './hosts.js';
-import { editProxy } from './proxy.js';
+import { editProxy, watchProxyChanges } from './proxy.js';
 import { updateStatusIndicator, updateNetworkStatusUI, showAboutModal, closeAboutModal } from './ui.js';
 import { initAutoSwitch, showAutoSwitchConfig, getAutoSwitchConfig } from './auto-switch.js';
 import { initHostsScheduledUpdate } from './hosts.js';
@@ -42,6 +42,8 @@ async function init() {
   initNetworkStatus();
   
   await loadScenes();
+  watchSceneFiles().catch(error => console.error('监听场景目录失败:', error));
+  watchProxyChanges().catch(error => console.error('监听代理设置失败:', error));
   await refreshNetworkInfo(true); // 初始化时显示加载提示
   setupEventListeners();
   
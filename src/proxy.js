@@ -1,20 +1,71 @@
 // 代理编辑相关功能
 
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { escapeHtml } from './utils.js';
 import { t } from './i18n.js';
 
 // 编辑代理 - 使用表单界面
 export async function editProxy() {
   try {
     const proxy = await invoke('get_proxy');
-    showProxyEditor(proxy);
+    const propagation = await invoke('get_proxy_propagation');
+    bypassPresets = await invoke('get_proxy_bypass_presets');
+    proxyProfiles = await invoke('list_proxy_profiles');
+    showProxyEditor(proxy, propagation);
   } catch (error) {
     alert(t('loadProxyFailed', { error }));
   }
 }
 
+// 监听后端推送的系统代理变化（包括 Clash 等其他程序的修改），刷新状态指示器
+export async function watchProxyChanges() {
+  await listen('proxy-changed', async (event) => {
+    if (window.updateStatusIndicator) {
+      await window.updateStatusIndicator();
+    }
+    // 编辑器打开时被其他程序修改，提示用户当前表单已过期
+    if (event.payload.source === 'external' && document.getElementById('proxy-enabled')) {
+      alert(t('proxyChangedExternally'));
+    }
+  });
+}
+
+// 可单独指定代理的协议（与 ProxyServers 字段对应）
+const PROXY_PROTOCOLS = ['http', 'https', 'ftp', 'socks'];
+
+// 可同步代理设置的目标（与后端 PropagationTarget 对应）
+const PROPAGATION_TARGETS = [
+  ['env', 'propagationEnv'],
+  ['git', 'propagationGit'],
+  ['npm', 'propagationNpm'],
+  ['pnpm', 'propagationPnpm'],
+  ['pip', 'propagationPip'],
+  ['winhttp', 'propagationWinHttp']
+];
+
+// 可以写入认证信息的目标（与后端 PropagationTarget::accepts_credential 对应）
+const CREDENTIAL_TARGETS = ['git', 'npm', 'pnpm', 'pip'];
+
+// 打开编辑器时的同步设置，保存时只在有变化时提交
+let currentPropagation = { targets: [], credentialTargets: [] };
+
+// 代理方案（第一个为内置的 direct）
+let proxyProfiles = [];
+
+// 绕过列表预设（与后端 bypass::presets 对应）
+let bypassPresets = [];
+const BYPASS_PRESET_LABELS = {
+  private: 'bypassPresetPrivate',
+  corporate: 'bypassPresetCorporate'
+};
+
 // 显示代理编辑器
-function showProxyEditor(proxy) {
+function showProxyEditor(proxy, propagation) {
+  currentPropagation = propagation;
+  loadedCredentialUser = null;
+  const servers = proxy.servers || {};
+  const perProtocol = PROXY_PROTOCOLS.some(protocol => servers[protocol]);
   const modal = document.createElement('div');
   modal.className = 'modal-overlay';
   modal.innerHTML = `
@@ -24,6 +75,22 @@ function showProxyEditor(proxy) {
         <button class="modal-close" onclick="this.closest('.modal-overlay').remove()">×</button>
       </div>
       <div class="modal-body">
+        <div class="form-group">
+          <label for="proxy-profile">${t('proxyProfile')}</label>
+          <div class="proxy-profile-row">
+            <select id="proxy-profile" class="form-input" onchange="window.loadProxyProfileCredential()">${renderProfileOptions()}</select>
+            <button type="button" class="btn btn-secondary" onclick="window.applyProxyProfile()">${t('proxyProfileApply')}</button>
+            <button type="button" class="btn btn-secondary" onclick="window.saveProxyProfile()">${t('proxyProfileSave')}</button>
+            <button type="button" class="btn btn-secondary" onclick="window.deleteProxyProfile()">${t('proxyProfileDelete')}</button>
+          </div>
+          <div class="proxy-profile-row">
+            <input type="text" id="proxy-credential-username" class="form-input" autocomplete="off"
+                   placeholder="${t('proxyCredentialUsername')}">
+            <input type="password" id="proxy-credential-password" class="form-input" autocomplete="new-password"
+                   placeholder="${t('proxyCredentialPassword')}">
+          </div>
+          <small class="form-hint">${t('proxyCredentialHint')}</small>
+        </div>
         <div class="form-group proxy-checkbox-group">
           <label class="checkbox-label">
             <input type="checkbox" id="proxy-enabled" ${proxy.enabled ? 'checked' : ''}>
@@ -48,17 +115,66 @@ function showProxyEditor(proxy) {
           <label for="proxy-server">${t('proxyServer')}</label>
           <input type="text" id="proxy-server" class="form-input" 
                  placeholder="127.0.0.1:8080" 
-                 value="${proxy.server || ''}">
+                 value="${servers.all || ''}" ${perProtocol ? 'disabled' : ''}>
           <small class="form-hint">${t('proxyServerHint')}</small>
+          <label class="checkbox-label">
+            <input type="checkbox" id="proxy-per-protocol" ${perProtocol ? 'checked' : ''}
+                   onchange="window.togglePerProtocolProxy(this.checked)">
+            <span>${t('proxyPerProtocol')}</span>
+          </label>
+        </div>
+        <div id="proxy-protocol-servers" style="display: ${perProtocol ? 'block' : 'none'}">
+          ${PROXY_PROTOCOLS.map(protocol => `
+            <div class="form-group">
+              <label for="proxy-server-${protocol}">${protocol.toUpperCase()}:</label>
+              <input type="text" id="proxy-server-${protocol}" class="form-input"
+                     placeholder="127.0.0.1:${protocol === 'socks' ? '1080' : '8080'}"
+                     value="${servers[protocol] || ''}">
+            </div>
+          `).join('')}
+        </div>
+        <div class="form-group">
+          <label>${t('proxyPropagation')}</label>
+          <div class="proxy-checkbox-group">
+            ${PROPAGATION_TARGETS.map(([target, key]) => `
+              <label class="checkbox-label">
+                <input type="checkbox" class="proxy-propagation-target" value="${target}"
+                       ${propagation.targets.includes(target) ? 'checked' : ''}>
+                <span>${t(key)}</span>
+              </label>
+            `).join('')}
+          </div>
+          <small class="form-hint">${t('proxyPropagationHint')}</small>
+          <label>${t('proxyPropagationCredential')}</label>
+          <div class="proxy-checkbox-group">
+            ${PROPAGATION_TARGETS.filter(([target]) => CREDENTIAL_TARGETS.includes(target)).map(([target, key]) => `
+              <label class="checkbox-label">
+                <input type="checkbox" class="proxy-credential-target" value="${target}"
+                       ${propagation.credentialTargets.includes(target) ? 'checked' : ''}>
+                <span>${t(key)}</span>
+              </label>
+            `).join('')}
+          </div>
+          <small class="form-hint">${t('proxyPropagationCredentialHint')}</small>
         </div>
         <div class="form-group">
           <label for="proxy-bypass">${t('proxyBypass')}</label>
           <textarea id="proxy-bypass" class="form-textarea" 
                     placeholder="localhost;127.0.0.1;*.local">${proxy.bypass?.join(';') || ''}</textarea>
           <small class="form-hint">${t('proxyBypassHint')}</small>
+          <div class="proxy-bypass-actions">
+            ${bypassPresets.map(preset => `
+              <button type="button" class="btn btn-secondary"
+                      onclick="window.applyBypassPreset('${preset.id}')">${t(BYPASS_PRESET_LABELS[preset.id] || preset.id)}</button>
+            `).join('')}
+            <button type="button" class="btn btn-secondary"
+                    onclick="window.checkProxyBypass()">${t('bypassCheck')}</button>
+          </div>
         </div>
       </div>
       <div class="modal-footer">
+        <button class="btn btn-secondary" onclick="window.editPacSettings()">${t('pacServer')}</button>
+        <button class="btn btn-secondary" onclick="window.testProxy()">${t('proxyTest')}</button>
         <button class="btn btn-secondary" onclick="this.closest('.modal-overlay').remove()">${t('cancel')}</button>
         <button class="btn btn-primary" onclick="window.saveProxy()">${t('save')}</button>
       </div>
@@ -67,30 +183,236 @@ function showProxyEditor(proxy) {
   document.body.appendChild(modal);
 }
 
-// 保存代理配置
-window.saveProxy = async function() {
+// 代理方案下拉选项
+function renderProfileOptions() {
+  return proxyProfiles.map(profile => {
+    const label = profile.builtin ? t('proxyProfileDirect') : profile.name;
+    return `<option value="${escapeHtml(profile.name)}">${escapeHtml(label)}</option>`;
+  }).join('');
+}
+
+function selectedProfileName() {
+  return document.getElementById('proxy-profile').value;
+}
+
+// 选中方案已保存的认证用户名（密码不会返回给前端）
+let loadedCredentialUser = null;
+
+window.loadProxyProfileCredential = async function() {
+  const usernameInput = document.getElementById('proxy-credential-username');
+  const passwordInput = document.getElementById('proxy-credential-password');
+  try {
+    loadedCredentialUser = await invoke('get_proxy_profile_username', { name: selectedProfileName() });
+  } catch (error) {
+    console.error('读取代理认证信息失败:', error);
+    loadedCredentialUser = null;
+  }
+  usernameInput.value = loadedCredentialUser || '';
+  passwordInput.value = '';
+  passwordInput.placeholder = loadedCredentialUser ? t('proxyCredentialSaved') : t('proxyCredentialPassword');
+};
+
+// 要随方案保存的认证信息：未修改时为 null（保留原有），清空用户名时删除
+function readProxyCredential() {
+  const username = document.getElementById('proxy-credential-username').value.trim();
+  const password = document.getElementById('proxy-credential-password').value;
+  if (!username) {
+    return loadedCredentialUser ? { username: '', password: '' } : null;
+  }
+  if (username === loadedCredentialUser && !password) {
+    return null;
+  }
+  return { username, password };
+}
+
+// 应用选中的代理方案并关闭编辑器
+window.applyProxyProfile = async function() {
+  try {
+    await invoke('apply_proxy_profile', { name: selectedProfileName() });
+    document.querySelector('.modal-overlay')?.remove();
+    if (window.updateStatusIndicator) {
+      await window.updateStatusIndicator();
+    }
+  } catch (error) {
+    alert(t('updateProxyFailed', { error }));
+  }
+};
+
+// 把编辑器中的设置保存为方案（同名覆盖）
+window.saveProxyProfile = async function() {
+  const config = readProxyConfig();
+  if (!config) return;
+  const source = selectedProfileName();
+  const current = proxyProfiles.find(p => p.name === source);
+  const name = prompt(t('proxyProfileNamePrompt'), current && !current.builtin ? current.name : '');
+  if (!name) return;
+  try {
+    // 另存为其他名称时由后端复制选中方案的认证信息（前端拿不到已保存的密码）
+    proxyProfiles = await invoke('save_proxy_profile', {
+      name,
+      config,
+      credential: readProxyCredential(),
+      source
+    });
+    const select = document.getElementById('proxy-profile');
+    select.innerHTML = renderProfileOptions();
+    select.value = name.trim();
+    await window.loadProxyProfileCredential();
+  } catch (error) {
+    alert(t('proxyProfileFailed', { error }));
+  }
+};
+
+// 删除选中的代理方案
+window.deleteProxyProfile = async function() {
+  const name = selectedProfileName();
+  if (!confirm(t('proxyProfileDeleteConfirm', { name }))) return;
+  try {
+    proxyProfiles = await invoke('delete_proxy_profile', { name });
+    document.getElementById('proxy-profile').innerHTML = renderProfileOptions();
+    await window.loadProxyProfileCredential();
+  } catch (error) {
+    alert(t('proxyProfileFailed', { error }));
+  }
+};
+
+// 切换“按协议分别设置”
+window.togglePerProtocolProxy = function(checked) {
+  document.getElementById('proxy-protocol-servers').style.display = checked ? 'block' : 'none';
+  document.getElementById('proxy-server').disabled = checked;
+};
+
+// 读取编辑器中的代理服务器
+function readProxyServers() {
+  const value = id => document.getElementById(id).value.trim() || null;
+  if (!document.getElementById('proxy-per-protocol').checked) {
+    return { all: value('proxy-server') };
+  }
+  const servers = {};
+  PROXY_PROTOCOLS.forEach(protocol => {
+    servers[protocol] = value(`proxy-server-${protocol}`);
+  });
+  return servers;
+}
+
+// 读取绕过列表（分号或换行分隔）
+function readBypass() {
+  return document.getElementById('proxy-bypass').value
+    .split(/[;\n]/)
+    .map(s => s.trim())
+    .filter(s => s);
+}
+
+// 把预设追加到绕过列表（跳过已有项）
+window.applyBypassPreset = function(id) {
+  const preset = bypassPresets.find(p => p.id === id);
+  if (!preset) return;
+  const bypass = readBypass();
+  const existing = new Set(bypass.map(s => s.toLowerCase()));
+  preset.rules.forEach(rule => {
+    if (!existing.has(rule.toLowerCase())) bypass.push(rule);
+  });
+  document.getElementById('proxy-bypass').value = bypass.join(';');
+};
+
+// 校验绕过列表，显示无效项及 CIDR 展开结果
+window.checkProxyBypass = async function() {
+  try {
+    const checks = await invoke('check_proxy_bypass', { entries: readBypass() });
+    const invalid = checks.filter(check => check.error);
+    if (invalid.length > 0) {
+      alert(t('bypassInvalid', { errors: invalid.map(check => check.error).join('\n') }));
+      return;
+    }
+    const expanded = checks
+      .filter(check => check.entries.length !== 1 || check.entries[0] !== check.input)
+      .map(check => `${check.input} → ${check.entries.join(';')}`);
+    alert(expanded.length > 0
+      ? t('bypassValidExpanded', { entries: expanded.join('\n') })
+      : t('bypassValid'));
+  } catch (error) {
+    alert(t('bypassInvalid', { errors: error }));
+  }
+};
+
+// 测试编辑器中的代理（不修改系统设置）
+window.testProxy = async function() {
+  const servers = readProxyServers();
+  if (!Object.values(servers).some(Boolean)) {
+    alert(t('proxyServerRequired'));
+    return;
+  }
+  try {
+    const result = await invoke('test_proxy', { servers, targetUrl: null, egressUrl: null });
+    const lines = [
+      t('proxyTestConnect', { proxy: result.proxy, ms: result.connectMs ?? '-' }),
+      t('proxyTestStatus', { status: result.status ?? '-' })
+    ];
+    if (result.authRequired) {
+      lines.push(t('proxyTestAuthRequired', { schemes: result.authSchemes.join(', ') || '-' }));
+    }
+    if (result.egressIp) {
+      lines.push(t('proxyTestEgressIp', { ip: result.egressIp }));
+    }
+    if (result.error) {
+      lines.push(t('proxyTestError', { error: result.error }));
+    }
+    alert(lines.join('\n'));
+  } catch (error) {
+    alert(t('proxyTestError', { error }));
+  }
+};
+
+// 读取编辑器中的代理设置（与后端 ProxyConfig 对应），未填写服务器时提示并返回 null
+function readProxyConfig() {
   const enabled = document.getElementById('proxy-enabled').checked;
-  const server = document.getElementById('proxy-server').value.trim();
-  const bypassText = document.getElementById('proxy-bypass').value.trim();
-  const autoDetect = document.getElementById('proxy-auto-detect').checked;
+  const servers = readProxyServers();
   const useScript = document.getElementById('proxy-use-script').checked;
-  const scriptUrl = document.getElementById('proxy-script-url').value.trim();
-  
-  if (enabled && !server) {
+
+  if (enabled && !Object.values(servers).some(Boolean)) {
     alert(t('proxyServerRequired'));
-    return;
+    return null;
   }
-  
-  const bypass = bypassText ? bypassText.split(';').map(s => s.trim()).filter(s => s) : [];
+
+  return {
+    enabled,
+    servers: enabled ? servers : {},
+    bypass: readBypass(),
+    autoDetect: document.getElementById('proxy-auto-detect').checked,
+    autoConfigUrl: useScript ? document.getElementById('proxy-script-url').value.trim() : ''
+  };
+}
+
+// 保存代理配置
+window.saveProxy = async function() {
+  const config = readProxyConfig();
+  if (!config) return;
   
   try {
-    await invoke('set_proxy', {
-      enabled,
-      server: enabled ? server : '',
-      bypass,
-      autoDetect: autoDetect,
-      autoConfigUrl: useScript ? scriptUrl : ''
-    });
+    const checked = selector => Array.from(document.querySelectorAll(`${selector}:checked`))
+      .map(input => input.value);
+    const propagation = {
+      targets: checked('.proxy-propagation-target'),
+      credentialTargets: checked('.proxy-credential-target')
+    };
+    // 新开启写入认证信息的目标时再次确认（密码会以明文保存在工具配置中）
+    const addedCredentialTargets = propagation.credentialTargets
+      .filter(target => !currentPropagation.credentialTargets.includes(target));
+    if (addedCredentialTargets.length > 0 &&
+        !confirm(t('proxyPropagationCredentialConfirm', { targets: addedCredentialTargets.join(', ') }))) {
+      return;
+    }
+    await invoke('set_proxy', config);
+    // set_proxy 已按原有目标同步，目标有变化时再提交一次
+    if (JSON.stringify(propagation) !== JSON.stringify(currentPropagation)) {
+      const results = await invoke('set_proxy_propagation', { settings: propagation });
+      const failed = results.filter(result => result.error);
+      if (failed.length > 0) {
+        alert(t('proxyPropagationFailed', {
+          errors: failed.map(result => `${result.target}: ${result.error}`).join('\n')
+        }));
+      }
+    }
     alert(t('proxyUpdated'));
     document.querySelector('.modal-overlay')?.remove();
     // 更新状态指示器中的代理状态
@@ -101,3 +423,141 @@ window.saveProxy = async function() {
     alert(t('updateProxyFailed', { error }));
   }
 };
+
+// 编辑本机 PAC 服务
+window.editPacSettings = async function() {
+  let settings;
+  try {
+    settings = await invoke('get_pac_settings');
+  } catch (error) {
+    alert(t('loadProxyFailed', { error }));
+    return;
+  }
+
+  const rulesText = settings.rules.map(rule => `${rule.pattern} ${rule.proxy}`).join('\n');
+  const modal = document.createElement('div');
+  modal.className = 'modal-overlay pac-modal-overlay';
+  modal.innerHTML = `
+    <div class="modal-content proxy-modal">
+      <div class="modal-header">
+        <h2>${t('pacServer')}</h2>
+        <button class="modal-close" onclick="this.closest('.modal-overlay').remove()">×</button>
+      </div>
+      <div class="modal-body">
+        <div class="form-group proxy-checkbox-group">
+          <label class="checkbox-label">
+            <input type="checkbox" id="pac-enabled" ${settings.enabled ? 'checked' : ''}>
+            <span>${t('pacEnabled')}</span>
+          </label>
+        </div>
+        <div class="form-group">
+          <label for="pac-port">${t('pacPort')}</label>
+          <input type="number" id="pac-port" class="form-input" min="1" max="65535" value="${settings.port}">
+        </div>
+        <div class="form-group">
+          <label for="pac-mode">${t('pacMode')}</label>
+          <select id="pac-mode" class="form-input" onchange="window.togglePacMode(this.value)">
+            <option value="generated" ${settings.mode === 'generated' ? 'selected' : ''}>${t('pacModeGenerated')}</option>
+            <option value="custom" ${settings.mode === 'custom' ? 'selected' : ''}>${t('pacModeCustom')}</option>
+          </select>
+        </div>
+        <div id="pac-generated-fields" style="display: ${settings.mode === 'custom' ? 'none' : 'block'}">
+          <div class="form-group">
+            <label for="pac-rules">${t('pacRules')}</label>
+            <textarea id="pac-rules" class="form-textarea"
+                      placeholder="*.example.com 127.0.0.1:7890">${rulesText}</textarea>
+            <small class="form-hint">${t('pacRulesHint')}</small>
+          </div>
+          <div class="form-group">
+            <label for="pac-bypass">${t('proxyBypass')}</label>
+            <textarea id="pac-bypass" class="form-textarea"
+                      placeholder="<local>;*.corp.example">${settings.bypass.join(';')}</textarea>
+          </div>
+          <div class="form-group">
+            <label for="pac-default-proxy">${t('pacDefaultProxy')}</label>
+            <input type="text" id="pac-default-proxy" class="form-input"
+                   placeholder="DIRECT" value="${settings.defaultProxy}">
+          </div>
+        </div>
+        <div id="pac-custom-fields" style="display: ${settings.mode === 'custom' ? 'block' : 'none'}">
+          <div class="form-group">
+            <label for="pac-script">${t('pacScript')}</label>
+            <textarea id="pac-script" class="form-textarea" rows="10"
+                      placeholder="function FindProxyForURL(url, host) { return &quot;DIRECT&quot;; }"></textarea>
+          </div>
+        </div>
+      </div>
+      <div class="modal-footer">
+        <button class="btn btn-secondary" onclick="window.previewPacScript()">${t('pacPreview')}</button>
+        <button class="btn btn-secondary" onclick="window.evaluatePac()">${t('pacEvaluate')}</button>
+        <button class="btn btn-secondary" onclick="this.closest('.modal-overlay').remove()">${t('cancel')}</button>
+        <button class="btn btn-primary" onclick="window.savePacSettings()">${t('save')}</button>
+      </div>
+    </div>
+  `;
+  document.body.appendChild(modal);
+  // 脚本内容可能包含引号等字符，直接赋值避免拼接 HTML
+  document.getElementById('pac-script').value = settings.script;
+};
+
+window.togglePacMode = function(mode) {
+  document.getElementById('pac-generated-fields').style.display = mode === 'custom' ? 'none' : 'block';
+  document.getElementById('pac-custom-fields').style.display = mode === 'custom' ? 'block' : 'none';
+};
+
+// 读取 PAC 编辑器中的设置
+function readPacSettings() {
+  const rules = document.getElementById('pac-rules').value
+    .split('\n')
+    .map(line => line.trim())
+    .filter(line => line)
+    .map(line => {
+      const index = line.search(/\s/);
+      return index < 0
+        ? { pattern: line, proxy: '' }
+        : { pattern: line.slice(0, index), proxy: line.slice(index).trim() };
+    });
+  return {
+    enabled: document.getElementById('pac-enabled').checked,
+    port: parseInt(document.getElementById('pac-port').value, 10) || 0,
+    mode: document.getElementById('pac-mode').value,
+    script: document.getElementById('pac-script').value,
+    rules,
+    bypass: document.getElementById('pac-bypass').value.split(';').map(s => s.trim()).filter(s => s),
+    defaultProxy: document.getElementById('pac-default-proxy').value.trim()
+  };
+}
+
+window.previewPacScript = async function() {
+  try {
+    const script = await invoke('preview_pac_script', { settings: readPacSettings() });
+    alert(script);
+  } catch (error) {
+    alert(t('pacSaveFailed', { error }));
+  }
+};
+
+window.savePacSettings = async function() {
+  try {
+    const url = await invoke('save_pac_settings', { settings: readPacSettings() });
+    alert(url ? t('pacSavedWithUrl', { url }) : t('pacSaved'));
+    document.querySelector('.pac-modal-overlay')?.remove();
+  } catch (error) {
+    alert(t('pacSaveFailed', { error }));
+  }
+};
+
+// 检查某个地址会使用哪个代理（使用系统当前的自动配置脚本）
+window.evaluatePac = async function() {
+  const url = prompt(t('pacEvaluatePrompt'), 'https://www.example.com/');
+  if (!url) return;
+  try {
+    const evaluation = await invoke('evaluate_pac', { url, source: null });
+    const chain = evaluation.chain
+      .map((hop, index) => `${index + 1}. ${hop.kind}${hop.server ? ' ' + hop.server : ''}`)
+      .join('\n');
+    alert(t('pacEvaluateResult', { host: evaluation.host, result: evaluation.result, chain }));
+  } catch (error) {
+    alert(t('pacEvaluateFailed', { error }));
+  }
+};
@@ -1,6 +1,7 @@
 // 场景管理相关功能
 
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import * as state from './state.js';
 import { getNetworkTypeInfo } from './network.js';
 import { renderNetworkInfo, refreshNetworkInfo } from './network.js';
@@ -28,6 +29,16 @@ export async function loadScenes() {
   }
 }
 
+// 监听后端推送的场景文件变化（外部编辑/新增/删除），自动刷新列表
+export async function watchSceneFiles() {
+  for (const event of ['scene-added', 'scene-changed', 'scene-removed']) {
+    await listen(event, () => loadScenes());
+  }
+  await listen('scene-invalid', (event) => {
+    console.warn('场景文件无效:', event.payload.file_name, event.payload.error);
+  });
+}
+
 // 渲染场景列表
 export async function renderScenes() {
   const container = document.getElementById('scenes-list');
@@ -406,10 +417,10 @@ window.saveSceneConfig = async function() {
     
     await invoke('update_scene', {
       sceneName,
-      networkConfigs,
-      hostsContent: null,
-      proxyConfig: null,
-      trayColor: validTrayColor
+      update: {
+        network_configs: networkConfigs,
+        tray_color: validTrayColor
+      }
     });
     
     // 关闭编辑器
@@ -444,11 +455,11 @@ window.applyScene = async function(sceneName) {
   renderScenes(); // 不等待，立即更新场景列表
   
   try {
-    // 后台执行场景应用，不阻塞UI
-    const applyPromise = invoke('apply_scene', { sceneName });
+    // 后台执行场景应用，不阻塞UI（共享来源的场景需要带上来源名称）
+    const scene = state.scenes.find(s => s.name === sceneName);
+    const applyPromise = invoke('apply_scene', { sceneName, source: scene?.source ?? null });
     
     // 应用托盘颜色（如果场景中有设置）
-    const scene = state.scenes.find(s => s.name === sceneName);
     if (scene && scene.tray_color) {
       // 托盘颜色更新也异步执行，不阻塞
       invoke('update_tray_icon_color', { hexColor: scene.tray_color }).then(() => {
@@ -536,9 +547,9 @@ window.importScenesConfig = async function() {
       reader.onload = async (e) => {
         try {
           const text = e.target.result;
-          await invoke('import_scenes_json', { json: text });
+          const publisher = await invoke('import_scenes_json', { json: text });
           await loadScenes();
-          alert(t('importScenesOk'));
+          alert(publisher ? t('importScenesOkPublisher', { publisher }) : t('importScenesOk'));
         } catch (error) {
           alert(t('importScenesFailed', { error }));
         }
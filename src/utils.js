@@ -1,8 +1,8 @@
 // 工具函数
 
-// HTML转义
+// HTML转义（也转义引号，可用于属性值）
 export function escapeHtml(text) {
   const div = document.createElement('div');
   div.textContent = text;
-  return div.innerHTML;
+  return div.innerHTML.replace(/"/g, '&quot;').replace(/'/g, '&#39;');
 }